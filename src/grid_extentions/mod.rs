@@ -1,8 +1,20 @@
+use core::ops::{Range, RangeInclusive};
+
 use coord_2d::Coord;
-use grid_2d::Grid;
+#[cfg(feature = "rayon")]
+use coord_2d::Size;
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
 
 use crate::prelude::*;
 
+mod storage;
+pub use self::storage::*;
+
+/// Drawing shapes onto and copying shapes off of any [`GridStorage`]
 pub trait ShapeGridExtensions<T> {
     /// Copy a shape off of the grid
     fn get_from_shape(&self, shape: impl Shape, unused_value: T) -> Self;
@@ -12,6 +24,16 @@ pub trait ShapeGridExtensions<T> {
     fn get_from_shape_offset(&self, shape: impl Shape, offset: Coord, unused_value: T) -> Self;
     /// Copy a shape onto the grid at an offset
     fn set_from_shape_offset(&mut self, shape: impl Shape, offset: Coord, value: T);
+    /// Copy a shape onto the grid, splitting the rows between threads
+    #[cfg(feature = "rayon")]
+    fn par_set_from_shape(&mut self, shape: impl Shape + Sync, value: T)
+    where
+        T: Send + Sync;
+    /// Copy a shape onto the grid at an offset, splitting the rows between threads
+    #[cfg(feature = "rayon")]
+    fn par_set_from_shape_offset(&mut self, shape: impl Shape + Sync, offset: Coord, value: T)
+    where
+        T: Send + Sync;
 
     /// Copy the border for a rectangle off of the grid
     fn get_from_rectangle_border(&self, rectangle: Rectangle, unused_value: T) -> Self;
@@ -80,63 +102,157 @@ pub trait ShapeGridExtensions<T> {
     );
 }
 
-impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
-    fn get_from_shape(&self, shape: impl Shape, unused_value: T) -> Self {
-        let rectangle = shape.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+/// Returns the area a shape may cover while landing on the grid once moved by `offset`
+fn bounds<T>(grid: &impl GridStorage<T>, offset: Coord) -> Rectangle {
+    Rectangle::from_size(Coord::new(-offset.x, -offset.y), grid.size())
+}
 
-        shape.for_each(|coord| {
-            let grid_position =
-                Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
-            if let Some(grid_value) = self.get(coord) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
-            }
-        });
+/// Returns the indices of the grid's cells covered by a span once moved by `offset`
+///
+/// The span must land on the grid.
+fn span_indices<T>(
+    grid: &impl GridStorage<T>,
+    y: i32,
+    span: &RangeInclusive<i32>,
+    offset: Coord,
+) -> Range<usize> {
+    let width = grid.size().width() as usize;
+    let start = (y + offset.y) as usize * width + (span.start() + offset.x) as usize;
+    start..start + (span.end() - span.start() + 1) as usize
+}
 
-        grid
+/// Sets the cells covered by a span once moved by `offset` to `value`
+///
+/// The span must land on the grid.
+fn fill_span<T: Copy>(
+    grid: &mut impl GridStorage<T>,
+    y: i32,
+    span: RangeInclusive<i32>,
+    offset: Coord,
+    value: T,
+) {
+    let indices = span_indices(grid, y, &span, offset);
+    if let Some(cells) = grid.cells_mut() {
+        cells[indices].fill(value);
+        return;
+    }
+
+    for x in span {
+        if let Some(cell) = grid.get_mut(Coord::new(x + offset.x, y + offset.y)) {
+            *cell = value;
+        }
+    }
+}
+
+/// Copies the cells covered by a span once moved by `offset` into `copy`, whose bottom left
+/// cell is `min`
+///
+/// The span must land on both grids.
+fn copy_span<T: Copy, G: GridStorage<T>>(
+    grid: &G,
+    copy: &mut G,
+    y: i32,
+    span: RangeInclusive<i32>,
+    offset: Coord,
+    min: Coord,
+) {
+    let indices = span_indices(grid, y, &span, offset);
+    let copy_indices = span_indices(copy, y, &span, -min);
+    if let (Some(cells), Some(copy_cells)) = (grid.cells(), copy.cells_mut()) {
+        copy_cells[copy_indices].copy_from_slice(&cells[indices]);
+        return;
+    }
+
+    for x in span {
+        let coord = Coord::new(x, y);
+        if let (Some(&value), Some(cell)) = (grid.get(coord + offset), copy.get_mut(coord - min)) {
+            *cell = value;
+        }
+    }
+}
+
+impl<T: Copy, G: GridStorage<T>> ShapeGridExtensions<T> for G {
+    fn get_from_shape(&self, shape: impl Shape, unused_value: T) -> Self {
+        self.get_from_shape_offset(shape, Coord::new(0, 0), unused_value)
     }
 
     fn set_from_shape(&mut self, shape: impl Shape, value: T) {
-        shape.for_each(|coord| {
-            if let Some(grid_value) = self.get_mut(coord) {
-                *grid_value = value;
-            }
-        });
+        self.set_from_shape_offset(shape, Coord::new(0, 0), value);
     }
 
     fn get_from_shape_offset(&self, shape: impl Shape, offset: Coord, unused_value: T) -> Self {
         let rectangle = shape.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
-        shape.for_each(|coord| {
-            let grid_position =
-                Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
-            let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
-            if let Some(grid_value) = self.get(position) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
-            }
-        });
+        for (y, span) in shape.spans_within(bounds(self, offset)) {
+            copy_span(self, &mut grid, y, span, offset, rectangle.min());
+        }
 
         grid
     }
 
     fn set_from_shape_offset(&mut self, shape: impl Shape, offset: Coord, value: T) {
-        shape.for_each(|coord| {
-            let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
-            if let Some(grid_value) = self.get_mut(position) {
-                *grid_value = value;
-            }
-        });
+        for (y, span) in shape.spans_within(bounds(self, offset)) {
+            fill_span(self, y, span, offset, value);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_set_from_shape(&mut self, shape: impl Shape + Sync, value: T)
+    where
+        T: Send + Sync,
+    {
+        self.par_set_from_shape_offset(shape, Coord::new(0, 0), value);
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_set_from_shape_offset(&mut self, shape: impl Shape + Sync, offset: Coord, value: T)
+    where
+        T: Send + Sync,
+    {
+        let size = self.size();
+        let width = size.width() as usize;
+        if width == 0 {
+            return;
+        }
+
+        // A few bands of rows per thread so shapes covering only part of the grid still spread out
+        let band_rows = (size.height() as usize)
+            .div_ceil(rayon::current_num_threads() * 4)
+            .max(1);
+
+        let Some(cells) = self.cells_mut() else {
+            self.set_from_shape_offset(shape, offset, value);
+            return;
+        };
+        cells
+            .par_chunks_mut(width * band_rows)
+            .enumerate()
+            .for_each(|(band, cells)| {
+                let bottom = (band * band_rows) as i32;
+                let rows = Size::new(width as u32, (cells.len() / width) as u32);
+                let bounds = Rectangle::from_size(Coord::new(-offset.x, bottom - offset.y), rows);
+
+                // Only the rows of this band are visited
+                for (y, span) in shape.spans_within(bounds) {
+                    let start = (y + offset.y - bottom) as usize * width
+                        + (span.start() + offset.x) as usize;
+                    let len = (span.end() - span.start() + 1) as usize;
+                    cells[start..start + len].fill(value);
+                }
+            });
     }
 
     fn get_from_rectangle_border(&self, rectangle: Rectangle, unused_value: T) -> Self {
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         rectangle.for_each_border(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             if let Some(grid_value) = self.get(coord) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -157,14 +273,16 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
         offset: Coord,
         unused_value: T,
     ) -> Self {
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         rectangle.for_each_border(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
             if let Some(grid_value) = self.get(position) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -182,13 +300,15 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
 
     fn get_from_circle_circumference(&self, circle: Circle, unused_value: T) -> Self {
         let rectangle = circle.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         circle.for_each_circumference(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             if let Some(grid_value) = self.get(coord) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -210,14 +330,16 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
         unused_value: T,
     ) -> Self {
         let rectangle = circle.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         circle.for_each_circumference(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
             if let Some(grid_value) = self.get(position) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -235,13 +357,15 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
 
     fn get_from_line_tunnel_horizontal_vertical(&self, line: Line, unused_value: T) -> Self {
         let rectangle = line.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         line.for_each_tunnel_horizontal_vertical(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             if let Some(grid_value) = self.get(coord) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -263,14 +387,16 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
         unused_value: T,
     ) -> Self {
         let rectangle = line.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         line.for_each_tunnel_horizontal_vertical(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
             if let Some(grid_value) = self.get(position) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -293,13 +419,15 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
 
     fn get_from_line_tunnel_vertical_horizontal(&self, line: Line, unused_value: T) -> Self {
         let rectangle = line.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         line.for_each_tunnel_vertical_horizontal(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             if let Some(grid_value) = self.get(coord) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -321,14 +449,16 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
         unused_value: T,
     ) -> Self {
         let rectangle = line.aabb();
-        let mut grid = Grid::new_copy(rectangle.size, unused_value);
+        let mut grid = Self::new_copy(rectangle.size, unused_value);
 
         line.for_each_tunnel_vertical_horizontal(|coord| {
             let grid_position =
                 Coord::new(coord.x - rectangle.left(), coord.y - rectangle.bottom());
             let position = Coord::new(coord.x + offset.x, coord.y + offset.y);
             if let Some(grid_value) = self.get(position) {
-                *grid.get_checked_mut(grid_position) = *grid_value;
+                if let Some(cell) = grid.get_mut(grid_position) {
+                    *cell = *grid_value;
+                }
             }
         });
 
@@ -349,3 +479,198 @@ impl<T: Copy> ShapeGridExtensions<T> for Grid<T> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::test_helpers::{count_contains_calls, Counted};
+    use coord_2d::Size;
+    use grid_2d::Grid;
+
+    #[test]
+    fn test_set_from_shape_off_grid() {
+        let circle = Circle::new(Coord::new(-3, 4), 9);
+        let offset = Coord::new(2, -1);
+
+        let mut grid = Grid::new_copy(Size::new(8, 6), false);
+        grid.set_from_shape(circle, true);
+        for (coord, &value) in grid.enumerate() {
+            assert_eq!(value, circle.contains(coord));
+        }
+
+        let mut grid = Grid::new_copy(Size::new(8, 6), false);
+        grid.set_from_shape_offset(circle, offset, true);
+        for (coord, &value) in grid.enumerate() {
+            assert_eq!(value, circle.contains(coord - offset));
+        }
+    }
+
+    #[test]
+    fn test_set_from_shape_huge() {
+        // Only the cells landing on the grid are visited
+        let line = Line::new(Coord::new(-20_000_000, 0), Coord::new(20_000_000, 9));
+        let mut grid = Grid::new_copy(Size::new(10, 10), false);
+        let checked = count_contains_calls(|| grid.set_from_shape(Counted(line), true));
+        assert!(checked <= 10 * 10, "{}", checked);
+
+        let mut expected = Grid::new_copy(Size::new(10, 10), false);
+        expected.set_from_shape(line, true);
+        assert!(grid.iter().eq(expected.iter()));
+        for (coord, &value) in grid.enumerate() {
+            assert_eq!(value, line.contains(coord));
+        }
+    }
+
+    #[test]
+    fn test_get_from_shape_off_grid() {
+        let line = Line::new(Coord::new(-4, -2), Coord::new(10, 5));
+        let grid = Grid::new_fn(Size::new(8, 6), |coord| coord.x + coord.y * 8);
+
+        let copy = grid.get_from_shape(line, -1);
+        let aabb = line.aabb();
+        for (coord, &value) in copy.enumerate() {
+            let position = coord + aabb.min();
+            let expected = if line.contains(position) {
+                grid.get(position).copied().unwrap_or(-1)
+            } else {
+                -1
+            };
+            assert_eq!(value, expected);
+        }
+    }
+
+    #[test]
+    fn test_get_from_shape_offset() {
+        let shape = Complement::new(
+            Circle::new(Coord::new(2, 2), 3),
+            Rectangle::new(-2, -2, 6, 6),
+        );
+        let offset = Coord::new(3, 1);
+        let grid = Grid::new_fn(Size::new(8, 6), |coord| coord.x + coord.y * 8);
+
+        let copy = grid.get_from_shape_offset(shape, offset, -1);
+        let aabb = shape.aabb();
+        for (coord, &value) in copy.enumerate() {
+            let position = coord + aabb.min();
+            let expected = if shape.contains(position) {
+                grid.get(position + offset).copied().unwrap_or(-1)
+            } else {
+                -1
+            };
+            assert_eq!(value, expected);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn check_par_set_from_shape(shape: impl Shape + Sync) {
+        for size in [
+            Size::new(0, 4),
+            Size::new(1, 1),
+            Size::new(13, 70),
+            Size::new(64, 9),
+        ] {
+            for offset in [Coord::new(0, 0), Coord::new(-3, 5)] {
+                let mut expected = Grid::new_copy(size, false);
+                expected.set_from_shape_offset(shape, offset, true);
+                let mut grid = Grid::new_copy(size, false);
+                grid.par_set_from_shape_offset(shape, offset, true);
+                assert!(grid.iter().eq(expected.iter()), "{:?} {:?}", shape, size);
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_set_from_shape() {
+        check_par_set_from_shape(Rectangle::new(2, 3, 7, 9));
+        check_par_set_from_shape(Circle::new(Coord::new(6, 20), 17));
+        check_par_set_from_shape(Line::new(Coord::new(-4, -2), Coord::new(30, 75)));
+
+        let mut grid = Grid::new_copy(Size::new(20, 20), false);
+        grid.par_set_from_shape(Rectangle::new(2, 3, 7, 9), true);
+        for (coord, &value) in grid.enumerate() {
+            assert_eq!(value, Rectangle::new(2, 3, 7, 9).contains(coord));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_set_from_shape_work() {
+        // Splitting the rows between threads checks no more cells than doing them in order
+        let shape = Counted(Complement::new(
+            Circle::new(Coord::new(40, 40), 30),
+            Rectangle::new(0, 0, 79, 79),
+        ));
+        let mut grid = Grid::new_copy(Size::new(80, 80), false);
+        let serial = count_contains_calls(|| grid.set_from_shape(shape, true));
+        let parallel = count_contains_calls(|| grid.par_set_from_shape(shape, true));
+        assert!(parallel <= serial, "{} {}", parallel, serial);
+    }
+
+    /// Cells stored in a `Vec` with rows padded out to `stride`
+    struct Strided {
+        size: Size,
+        stride: usize,
+        cells: Vec<i32>,
+    }
+
+    impl GridStorage<i32> for Strided {
+        fn new_copy(size: Size, value: i32) -> Self {
+            let stride = size.width() as usize + 3;
+            Self {
+                size,
+                stride,
+                cells: vec![value; stride * size.height() as usize],
+            }
+        }
+
+        fn size(&self) -> Size {
+            self.size
+        }
+
+        fn get(&self, coord: Coord) -> Option<&i32> {
+            coord
+                .is_valid(self.size)
+                .then(|| &self.cells[coord.y as usize * self.stride + coord.x as usize])
+        }
+
+        fn get_mut(&mut self, coord: Coord) -> Option<&mut i32> {
+            coord
+                .is_valid(self.size)
+                .then(|| &mut self.cells[coord.y as usize * self.stride + coord.x as usize])
+        }
+    }
+
+    #[test]
+    fn test_storage() {
+        let size = Size::new(8, 6);
+        let value = |coord: Coord| coord.x + coord.y * 8;
+        let shape = Complement::new(
+            Circle::new(Coord::new(2, 2), 3),
+            Rectangle::new(-2, -2, 6, 6),
+        );
+        let offset = Coord::new(3, 1);
+
+        let mut strided = Strided::new_copy(size, 0);
+        let mut grid = Grid::new_copy(size, 0);
+        for coord in Rectangle::from_size(Coord::new(0, 0), size) {
+            *GridStorage::get_mut(&mut strided, coord).unwrap() = value(coord);
+            *grid.get_checked_mut(coord) = value(coord);
+        }
+
+        let strided_copy = strided.get_from_shape_offset(shape, offset, -1);
+        let copy = grid.get_from_shape_offset(shape, offset, -1);
+        assert_eq!(strided_copy.size, copy.size());
+        for (coord, value) in copy.enumerate() {
+            assert_eq!(GridStorage::get(&strided_copy, coord), Some(value));
+        }
+
+        strided.set_from_shape_offset(shape, offset, -2);
+        strided.set_from_circle_circumference(Circle::new(Coord::new(4, 4), 2), -3);
+        grid.set_from_shape_offset(shape, offset, -2);
+        grid.set_from_circle_circumference(Circle::new(Coord::new(4, 4), 2), -3);
+        for (coord, value) in grid.enumerate() {
+            assert_eq!(GridStorage::get(&strided, coord), Some(value));
+        }
+    }
+}
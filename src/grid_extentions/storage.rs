@@ -0,0 +1,59 @@
+use coord_2d::{Coord, Size};
+use grid_2d::Grid;
+
+/// Storage of a grid of cells the shape extensions can read and write
+///
+/// Coords run from `(0, 0)` to just below `size`, with `y` counting rows.
+pub trait GridStorage<T>: Sized {
+    /// Creates storage of `size` with every cell set to `value`
+    ///
+    /// Used for the copies taken off of a grid.
+    fn new_copy(size: Size, value: T) -> Self;
+
+    /// Get the size of the grid
+    fn size(&self) -> Size;
+
+    /// Get the cell at `coord`, `None` if it is off of the grid
+    fn get(&self, coord: Coord) -> Option<&T>;
+
+    /// Get the cell at `coord` mutably, `None` if it is off of the grid
+    fn get_mut(&mut self, coord: Coord) -> Option<&mut T>;
+
+    /// Get every cell in a single slice row by row, if they are stored that way
+    ///
+    /// Allows whole rows to be copied and filled at once.
+    fn cells(&self) -> Option<&[T]> {
+        None
+    }
+
+    /// Get every cell mutably in a single slice row by row, if they are stored that way
+    fn cells_mut(&mut self) -> Option<&mut [T]> {
+        None
+    }
+}
+
+impl<T: Clone> GridStorage<T> for Grid<T> {
+    fn new_copy(size: Size, value: T) -> Self {
+        Grid::new_clone(size, value)
+    }
+
+    fn size(&self) -> Size {
+        Grid::size(self)
+    }
+
+    fn get(&self, coord: Coord) -> Option<&T> {
+        Grid::get(self, coord)
+    }
+
+    fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        Grid::get_mut(self, coord)
+    }
+
+    fn cells(&self) -> Option<&[T]> {
+        Some(self.raw())
+    }
+
+    fn cells_mut(&mut self) -> Option<&mut [T]> {
+        Some(self.raw_mut())
+    }
+}
@@ -1,3 +1,8 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(feature = "extentions")]
 pub mod grid_extentions;
 
@@ -5,16 +10,28 @@ pub mod shapes;
 
 pub mod prelude {
     // Trait
-    pub use crate::shapes::Shape;
+    pub use crate::shapes::{Intersects, Shape};
+
+    // Distances
+    pub use crate::shapes::DistanceMetric;
 
     // Circles
-    pub use crate::shapes::Circle;
+    pub use crate::shapes::{Circle, RingKind};
+
+    // Clipped
+    pub use crate::shapes::Clipped;
+
+    // Complements
+    pub use crate::shapes::Complement;
 
     // Lines
-    pub use crate::shapes::Line;
+    pub use crate::shapes::{CastResult, CastStart, Line, LineKind};
+
+    // Outlines
+    pub use crate::shapes::{Connectivity, Outline, OutlineKind};
 
     // Rectangles
-    pub use crate::shapes::Rectangle;
+    pub use crate::shapes::{Rectangle, TraversalOrder};
 
     // Extensions
     #[cfg(feature = "extentions")]
@@ -1,22 +1,46 @@
-use std::fmt::{Debug, Display};
+use core::{
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
+
+#[cfg(all(feature = "rand", feature = "alloc"))]
+use alloc::vec::Vec;
 
 use coord_2d::Coord;
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::{
-    iters::{CircleCircumferenceIter, CircleIter},
-    Rectangle, Shape,
+    iters::{midpoint, CircleCircumferenceIter, CircleIter, CircleRingIter},
+    DistanceMetric, Rectangle, Shape,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// Represents a Circle on a grid
 pub struct Circle {
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
     pub center: Coord,
     pub radius: u32,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// How the rings around the center of a circle are shaped
+pub enum RingKind {
+    /// Rings follow the circumference of a circle of each radius
+    #[default]
+    Euclidean,
+    /// Rings are squares, diagonal steps cost the same as orthogonal ones
+    Chebyshev,
+    /// Rings are diamonds, only orthogonal steps are allowed
+    Manhattan,
+}
+
 // Constructors
 impl Circle {
     /// Creates a new circle.
@@ -52,14 +76,58 @@ impl Circle {
         Coord::new(self.center.x, self.center.y - self.radius as i32)
     }
 
+    /// Returns the first and last column of the circle in row `y`
+    pub(crate) fn row_span(self, y: i32) -> Option<(i32, i32)> {
+        let half_width = midpoint::half_width(self.radius, y.abs_diff(self.center.y))? as i32;
+        Some((self.center.x - half_width, self.center.x + half_width))
+    }
+
+    /// Returns the first and last column of row `y` and how many cells from each end are part of
+    /// the perimeter
+    fn perimeter_row(self, y: i32) -> Option<(i32, i32, i32)> {
+        let dy = y.abs_diff(self.center.y);
+        let half_width = midpoint::half_width(self.radius, dy)? as i32;
+        // Rows get narrower away from the center, so only the next row outwards can leave cells
+        // without a neighbour above or below
+        let edge = match midpoint::half_width(self.radius, dy + 1) {
+            Some(next) => (half_width - next as i32).max(1),
+            None => 2 * half_width + 1,
+        };
+        Some((self.center.x - half_width, self.center.x + half_width, edge))
+    }
+
+    /// Returns the closest to `position` of the cells `cells` picks from each row
+    fn closest_row_cell<I: IntoIterator<Item = Coord>>(
+        self,
+        position: Coord,
+        metric: DistanceMetric,
+        cells: impl Fn(i32) -> I,
+    ) -> Option<Coord> {
+        let aabb = self.aabb();
+        let column = position.x.abs_diff(self.center.x) as f64;
+        metric.closest_along(
+            position,
+            aabb.bottom() as i64..=aabb.top() as i64,
+            |y| {
+                let half_width =
+                    midpoint::max_half_width(self.radius, y.abs_diff(self.center.y as i64));
+                (
+                    (column - half_width).max(0.0),
+                    y.abs_diff(position.y as i64) as f64,
+                )
+            },
+            |y| cells(y as i32),
+        )
+    }
+
     /// Get the number of cells inside the circle
     pub fn get_count(self) -> u32 {
-        self.into_iter().count() as u32
+        self.cell_count() as u32
     }
 
     /// Determine if a position is inside the circle
     pub fn contains(self, position: Coord) -> bool {
-        self.into_iter().find(|&c| c == position).is_some()
+        Shape::contains(self, position)
     }
 }
 
@@ -70,6 +138,30 @@ impl Circle {
         CircleCircumferenceIter::new(self.center, self.radius)
     }
 
+    /// Returns the cell of the circumference closest to `position`
+    ///
+    /// Ties are broken as described by [`DistanceMetric::is_closer`].
+    pub fn closest_circumference_cell(self, position: Coord, metric: DistanceMetric) -> Coord {
+        // Closest cell of the perimeter at each end of each row
+        self.closest_row_cell(position, metric, |y| {
+            self.perimeter_row(y)
+                .into_iter()
+                .flat_map(move |(left, right, edge)| {
+                    [
+                        Coord::new(position.x.clamp(left, (left + edge - 1).min(right)), y),
+                        Coord::new(position.x.clamp((right - edge + 1).max(left), right), y),
+                    ]
+                })
+        })
+        .unwrap_or(self.center)
+    }
+
+    /// Provides an iterator over the cells of the circle ring by ring from the center, along with
+    /// the index of each ring
+    pub fn ring_iter(self, kind: RingKind) -> CircleRingIter {
+        CircleRingIter::new_within(self.center, self.radius, kind)
+    }
+
     /// Calls `f` for each Coord in the circumference
     pub fn for_each_circumference<F: FnMut(Coord)>(self, mut f: F) {
         for coord in self.circumference_iter() {
@@ -78,6 +170,19 @@ impl Circle {
     }
 }
 
+// Parallel
+#[cfg(feature = "rayon")]
+impl Circle {
+    /// Provides a parallel iterator over the cells of the circle, split by the span of each row
+    pub fn par_iter(self) -> impl ParallelIterator<Item = Coord> {
+        let aabb = self.aabb();
+        (aabb.bottom()..=aabb.top())
+            .into_par_iter()
+            .filter_map(move |y| Some((y, self.row_span(y)?)))
+            .flat_map_iter(|(y, (left, right))| (left..=right).map(move |x| Coord::new(x, y)))
+    }
+}
+
 // Shape
 impl Shape for Circle {
     fn for_each<F: FnMut(Coord)>(self, mut f: F) {
@@ -86,6 +191,35 @@ impl Shape for Circle {
         }
     }
 
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, mut f: F) {
+        let Some(overlap) = self.aabb().intersection(bounds) else {
+            return;
+        };
+
+        // Only the rows and columns inside the bounds
+        for y in overlap.bottom()..=overlap.top() {
+            if let Some((left, right)) = self.row_span(y) {
+                for x in left.max(overlap.left())..=right.min(overlap.right()) {
+                    f(Coord::new(x, y));
+                }
+            }
+        }
+    }
+
+    fn spans_within(self, bounds: Rectangle) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        // Only the rows and columns inside the bounds
+        self.aabb()
+            .intersection(bounds)
+            .into_iter()
+            .flat_map(move |overlap| {
+                (overlap.bottom()..=overlap.top()).filter_map(move |y| {
+                    let (left, right) = self.row_span(y)?;
+                    let (left, right) = (left.max(overlap.left()), right.min(overlap.right()));
+                    (left <= right).then_some((y, left..=right))
+                })
+            })
+    }
+
     fn aabb(self) -> Rectangle {
         Rectangle::new(
             self.center.x - self.radius as i32,
@@ -94,6 +228,90 @@ impl Shape for Circle {
             self.center.y + self.radius as i32,
         )
     }
+
+    fn cell_count(self) -> usize {
+        (0..=self.radius).fold(0, |count, y| {
+            let row = 2 * midpoint::half_width(self.radius, y).unwrap_or(0) as usize + 1;
+            // Rows above and below the center
+            count + if y == 0 { row } else { row * 2 }
+        })
+    }
+
+    fn perimeter_iter(self) -> impl Iterator<Item = Coord> {
+        let aabb = self.aabb();
+        (aabb.bottom()..=aabb.top())
+            .filter_map(move |y| Some((y, self.perimeter_row(y)?)))
+            .flat_map(|(y, (left, right, edge))| {
+                // Both ends of the row, without overlapping
+                let left_end = (left + edge - 1).min(right);
+                let right_start = (right - edge + 1).max(left_end + 1);
+                (left..=left_end)
+                    .chain(right_start..=right)
+                    .map(move |x| Coord::new(x, y))
+            })
+    }
+
+    fn perimeter_len(self) -> usize {
+        let aabb = self.aabb();
+        (aabb.bottom()..=aabb.top())
+            .filter_map(|y| self.perimeter_row(y))
+            .map(|(left, right, edge)| (2 * edge).min(right - left + 1) as usize)
+            .sum()
+    }
+
+    fn closest_cell(self, position: Coord, metric: DistanceMetric) -> Option<Coord> {
+        if self.contains(position) {
+            return Some(position);
+        }
+
+        // Closest cell of each row
+        self.closest_row_cell(position, metric, |y| {
+            let (left, right) = self.row_span(y)?;
+            Some(Coord::new(position.x.clamp(left, right), y))
+        })
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        midpoint::contains(
+            self.radius,
+            position.x - self.center.x,
+            position.y - self.center.y,
+        )
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> Option<Coord> {
+        // The circle always fills over half of its bounding box
+        let aabb = self.aabb();
+        loop {
+            let coord = Coord::new(
+                rng.gen_range(aabb.left()..=aabb.right()),
+                rng.gen_range(aabb.bottom()..=aabb.top()),
+            );
+            if self.contains(coord) {
+                return Some(coord);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_n<R: Rng + ?Sized>(self, rng: &mut R, amount: usize) -> Vec<Coord> {
+        // Number of cells before each row, to find the row of an index
+        let aabb = self.aabb();
+        let mut rows = Vec::with_capacity(aabb.size.height() as usize);
+        let mut count = 0;
+        for y in aabb.bottom()..=aabb.top() {
+            let (left, right) = self.row_span(y).unwrap_or((0, -1));
+            rows.push((count, left, y));
+            count += (right - left + 1) as usize;
+        }
+
+        super::sample::sample_indices(rng, count, amount, |i| {
+            let row = rows.partition_point(|&(start, _, _)| start <= i) - 1;
+            let (start, left, y) = rows[row];
+            Coord::new(left + (i - start) as i32, y)
+        })
+    }
 }
 
 impl IntoIterator for Circle {
@@ -114,7 +332,7 @@ impl Default for Circle {
 }
 
 impl Debug for Circle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Circle {{ center: ({}, {}), radius: {} }}",
@@ -124,7 +342,7 @@ impl Debug for Circle {
 }
 
 impl Display for Circle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Circle {{\n\tcenter: ({}, {}),\n\tradius: {},\n}}",
@@ -132,3 +350,181 @@ impl Display for Circle {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_cell_count() {
+        for radius in (0..=40).chain([97, 256]) {
+            let circle = Circle::new(Coord::new(2, 3), radius);
+            assert_eq!(circle.cell_count(), circle.into_iter().count());
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        for radius in (0..=40).chain([97, 256]) {
+            let circle = Circle::new(Coord::new(radius as i32 % 7 - 3, -5), radius);
+            let points: HashSet<Coord> = circle.into_iter().collect();
+            for coord in circle.aabb().inflate(1, 1) {
+                assert_eq!(
+                    circle.contains(coord),
+                    points.contains(&coord),
+                    "{:?} {:?}",
+                    circle,
+                    coord
+                );
+            }
+        }
+    }
+
+    /// Steps the midpoint algorithm the way the iterators did before they were allocation free
+    fn midpoint_octants(radius: u32, mut f: impl FnMut(i32, i32)) {
+        let (mut d, mut x, mut y) = ((5 - radius as i32 * 4) / 4, 0, radius as i32);
+        while x <= y {
+            f(x, y);
+            if d < 0 {
+                d += 2 * x + 1;
+            } else {
+                d += 2 * (x - y) + 1;
+                y -= 1;
+            }
+            x += 1;
+        }
+    }
+
+    fn check_unique(points: &[Coord]) -> HashSet<Coord> {
+        let unique: HashSet<Coord> = points.iter().copied().collect();
+        assert_eq!(unique.len(), points.len());
+        unique
+    }
+
+    #[test]
+    fn test_iter() {
+        for radius in (0..=40).chain([97, 256]) {
+            let circle = Circle::new(Coord::new(-4, 7), radius);
+            let mut expected = HashSet::new();
+            midpoint_octants(radius, |x, y| {
+                for (column, height) in [(x, y), (-x, y), (y, x), (-y, x)] {
+                    for row in -height..=height {
+                        expected.insert(circle.center + Coord::new(column, row));
+                    }
+                }
+            });
+            let points: Vec<Coord> = circle.into_iter().collect();
+            assert_eq!(check_unique(&points), expected, "{:?}", circle);
+        }
+    }
+
+    #[test]
+    fn test_circumference_iter() {
+        for radius in (0..=40).chain([97, 256]) {
+            let circle = Circle::new(Coord::new(-4, 7), radius);
+            let mut expected = HashSet::new();
+            midpoint_octants(radius, |x, y| {
+                for (dx, dy) in [(x, y), (y, x)] {
+                    for (sx, sy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                        expected.insert(circle.center + Coord::new(dx * sx, dy * sy));
+                    }
+                }
+            });
+            let points: Vec<Coord> = circle.circumference_iter().collect();
+            assert_eq!(check_unique(&points), expected, "{:?}", circle);
+        }
+    }
+
+    fn ring_of(kind: RingKind, offset: Coord) -> u32 {
+        let (dx, dy) = (offset.x.unsigned_abs(), offset.y.unsigned_abs());
+        match kind {
+            RingKind::Euclidean => (0..)
+                .find(|&radius| midpoint::contains(radius, offset.x, offset.y))
+                .unwrap(),
+            RingKind::Chebyshev => dx.max(dy),
+            RingKind::Manhattan => dx + dy,
+        }
+    }
+
+    fn check_rings(rings: impl Iterator<Item = (u32, Coord)>, center: Coord, kind: RingKind) {
+        let mut last = 0;
+        for (ring, coord) in rings {
+            assert!(ring >= last, "{:?} {:?}", kind, coord);
+            assert_eq!(
+                ring,
+                ring_of(kind, coord - center),
+                "{:?} {:?}",
+                kind,
+                coord
+            );
+            last = ring;
+        }
+    }
+
+    #[test]
+    fn test_ring_iter() {
+        for radius in (0..=30).chain([97]) {
+            let circle = Circle::new(Coord::new(3, -8), radius);
+            let expected: HashSet<Coord> = circle.into_iter().collect();
+            for kind in [
+                RingKind::Euclidean,
+                RingKind::Chebyshev,
+                RingKind::Manhattan,
+            ] {
+                let points: Vec<Coord> = circle.ring_iter(kind).map(|(_, coord)| coord).collect();
+                assert_eq!(check_unique(&points), expected, "{:?} {:?}", circle, kind);
+                check_rings(circle.ring_iter(kind), circle.center, kind);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ring_iter_max_radius() {
+        let center = Coord::new(-2, 5);
+        for max_radius in 0..=12 {
+            let euclidean: Vec<Coord> =
+                CircleRingIter::new(center, max_radius, RingKind::Euclidean)
+                    .map(|(_, coord)| coord)
+                    .collect();
+            let circle: HashSet<Coord> = Circle::new(center, max_radius).into_iter().collect();
+            assert_eq!(check_unique(&euclidean), circle);
+
+            let side = 2 * max_radius as usize + 1;
+            let chebyshev: Vec<Coord> =
+                CircleRingIter::new(center, max_radius, RingKind::Chebyshev)
+                    .map(|(_, coord)| coord)
+                    .collect();
+            assert_eq!(check_unique(&chebyshev).len(), side * side);
+
+            let manhattan: Vec<Coord> =
+                CircleRingIter::new(center, max_radius, RingKind::Manhattan)
+                    .map(|(_, coord)| coord)
+                    .collect();
+            assert_eq!(check_unique(&manhattan).len(), (side * side).div_ceil(2));
+
+            for kind in [
+                RingKind::Euclidean,
+                RingKind::Chebyshev,
+                RingKind::Manhattan,
+            ] {
+                check_rings(CircleRingIter::new(center, max_radius, kind), center, kind);
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_iter() {
+        use rayon::iter::ParallelIterator;
+
+        for radius in (0..=20).chain([97]) {
+            let circle = Circle::new(Coord::new(-4, 7), radius);
+            let mut points: Vec<Coord> = circle.par_iter().collect();
+            let mut expected: Vec<Coord> = circle.into_iter().collect();
+            points.sort();
+            expected.sort();
+            assert_eq!(points, expected);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use core::{
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
+
+use coord_2d::{Coord, Size};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{iters::SpansIter, Rectangle, Shape};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Represents the cells of a shape which are inside some bounds
+pub struct Clipped<S: Shape> {
+    pub shape: S,
+    pub bounds: Rectangle,
+}
+
+// Constructors
+impl<S: Shape> Clipped<S> {
+    /// Creates a new Clipped `shape` limited to `bounds`
+    pub fn new(shape: S, bounds: Rectangle) -> Self {
+        Self { shape, bounds }
+    }
+}
+
+// Implementation
+impl<S: Shape> Clipped<S> {
+    /// Get the shape being clipped
+    pub fn shape(self) -> S {
+        self.shape
+    }
+
+    /// Get the bounds the shape is clipped to
+    pub fn bounds(self) -> Rectangle {
+        self.bounds
+    }
+}
+
+// Shape
+impl<S: Shape> Shape for Clipped<S> {
+    fn for_each<F: FnMut(Coord)>(self, f: F) {
+        self.shape.for_each_within(self.bounds, f);
+    }
+
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, f: F) {
+        if let Some(overlap) = self.bounds.intersection(bounds) {
+            self.shape.for_each_within(overlap, f);
+        }
+    }
+
+    fn spans_within(self, bounds: Rectangle) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        let overlap = self
+            .bounds
+            .intersection(bounds)
+            .unwrap_or_else(|| Rectangle::from_size(bounds.position, Size::new(0, 0)));
+        self.shape.spans_within(overlap)
+    }
+
+    fn aabb(self) -> Rectangle {
+        self.shape
+            .aabb()
+            .intersection(self.bounds)
+            .unwrap_or_else(|| Rectangle::from_size(self.bounds.position, Size::new(0, 0)))
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        self.bounds.contains(position) && self.shape.contains(position)
+    }
+}
+
+impl<S: Shape> IntoIterator for Clipped<S> {
+    type IntoIter = SpansIter<S>;
+    type Item = Coord;
+    fn into_iter(self) -> Self::IntoIter {
+        SpansIter::new(self.shape, self.aabb())
+    }
+}
+
+impl<S: Shape> Debug for Clipped<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Clipped {{ shape: {:?}, bounds: {:?} }}",
+            self.shape, self.bounds
+        )
+    }
+}
+
+impl<S: Shape> Display for Clipped<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Clipped {{\n\tshape: {:?},\n\tbounds: {:?},\n}}",
+            self.shape, self.bounds
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::test_helpers::{count_contains_calls, Counted};
+    use crate::shapes::{Circle, Complement, Connectivity, Line, OutlineKind};
+
+    fn bounds() -> Vec<Rectangle> {
+        vec![
+            Rectangle::new(0, 0, 9, 9),
+            Rectangle::new(-3, 2, 1, 4),
+            Rectangle::new(-20, -20, 20, -6),
+            Rectangle::new(30, 30, 40, 40),
+        ]
+    }
+
+    fn check(shape: impl Shape) {
+        for bounds in bounds() {
+            let clipped = Clipped::new(shape, bounds);
+            let mut expected = Vec::new();
+            shape.for_each(|coord| {
+                if bounds.contains(coord) {
+                    expected.push(coord);
+                }
+            });
+
+            let mut points = Vec::new();
+            clipped.for_each(|coord| points.push(coord));
+            let mut iterated: Vec<Coord> = clipped.into_iter().collect();
+
+            expected.sort();
+            points.sort();
+            iterated.sort();
+            assert_eq!(points, expected, "{:?}", clipped);
+            assert_eq!(iterated, expected, "{:?}", clipped);
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        check(Rectangle::new(-5, -5, 5, 5));
+        check(Rectangle::new(2, 3, 4, 3));
+    }
+
+    #[test]
+    fn test_circle() {
+        check(Circle::new(Coord::new(0, 0), 0));
+        check(Circle::new(Coord::new(0, 0), 8));
+        check(Circle::new(Coord::new(-1, 7), 13));
+    }
+
+    #[test]
+    fn test_line() {
+        check(Line::new(Coord::new(-30, -12), Coord::new(25, 17)));
+        check(Line::new(Coord::new(3, 15), Coord::new(-2, -15)));
+    }
+
+    #[test]
+    fn test_line_huge() {
+        // Only the cells inside the bounds are visited
+        let line = Line::new(Coord::new(-20_000_000, 0), Coord::new(20_000_000, 9));
+        let bounds = Rectangle::new(0, 0, 9, 9);
+        let cells: Vec<Coord> = Clipped::new(line, bounds).into_iter().collect();
+        assert_eq!(cells, line.clipped_iter(bounds).collect::<Vec<_>>());
+
+        // Each cell is checked once, plus the cell ending each span once more
+        let mut counted = Vec::new();
+        let checked = count_contains_calls(|| {
+            counted = Clipped::new(Counted(line), bounds).into_iter().collect();
+        });
+        assert_eq!(counted, cells);
+        assert!(checked <= bounds.cell_count() + cells.len(), "{}", checked);
+    }
+
+    #[test]
+    fn test_adapters() {
+        let circle = Circle::new(Coord::new(2, 2), 6);
+        check(circle.outline(Connectivity::Eight, OutlineKind::Outer));
+        check(Complement::new(circle, Rectangle::new(-4, -6, 12, 3)));
+        check(Clipped::new(circle, Rectangle::new(-1, -1, 3, 3)));
+    }
+}
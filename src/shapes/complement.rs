@@ -0,0 +1,131 @@
+use core::fmt::{Debug, Display};
+
+use coord_2d::Coord;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{iters::FilteredIter, Rectangle, Shape};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Represents every cell within some bounds which is not part of a shape
+pub struct Complement<S: Shape> {
+    pub shape: S,
+    pub bounds: Rectangle,
+}
+
+// Constructors
+impl<S: Shape> Complement<S> {
+    /// Creates a new Complement of `shape` within `bounds`
+    pub fn new(shape: S, bounds: Rectangle) -> Self {
+        Self { shape, bounds }
+    }
+}
+
+// Implementation
+impl<S: Shape> Complement<S> {
+    /// Get the shape being excluded
+    pub fn shape(self) -> S {
+        self.shape
+    }
+
+    /// Get the bounds of the complement
+    pub fn bounds(self) -> Rectangle {
+        self.bounds
+    }
+}
+
+// Shape
+impl<S: Shape> Shape for Complement<S> {
+    fn for_each<F: FnMut(Coord)>(self, mut f: F) {
+        for coord in self {
+            f(coord);
+        }
+    }
+
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, f: F) {
+        if let Some(overlap) = self.bounds.intersection(bounds) {
+            Complement::new(self.shape, overlap).for_each(f);
+        }
+    }
+
+    fn aabb(self) -> Rectangle {
+        self.bounds
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        self.bounds.contains(position) && !self.shape.contains(position)
+    }
+}
+
+impl<S: Shape> IntoIterator for Complement<S> {
+    type IntoIter = FilteredIter<Self>;
+    type Item = Coord;
+    fn into_iter(self) -> Self::IntoIter {
+        FilteredIter::new(self, self.bounds)
+    }
+}
+
+impl<S: Shape> Debug for Complement<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Complement {{ shape: {:?}, bounds: {:?} }}",
+            self.shape, self.bounds
+        )
+    }
+}
+
+impl<S: Shape> Display for Complement<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Complement {{\n\tshape: {:?},\n\tbounds: {:?},\n}}",
+            self.shape, self.bounds
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Circle;
+
+    fn bounds() -> Rectangle {
+        Rectangle::new(0, 0, 3, 3)
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let complement = Complement::new(Rectangle::new(1, 1, 3, 3), bounds());
+        let points: Vec<Coord> = complement.into_iter().collect();
+        assert_eq!(
+            points,
+            vec![
+                Coord::new(0, 0),
+                Coord::new(1, 0),
+                Coord::new(2, 0),
+                Coord::new(3, 0),
+                Coord::new(0, 1),
+                Coord::new(0, 2),
+                Coord::new(0, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partition() {
+        let circle = Circle::new(Coord::new(2, 1), 2);
+        let complement = Complement::new(circle, bounds());
+        for coord in bounds() {
+            assert_ne!(circle.contains(coord), complement.contains(coord));
+        }
+        assert!(!complement.contains(Coord::new(4, 4)));
+    }
+
+    #[test]
+    fn test_outside_bounds() {
+        let complement = Complement::new(Rectangle::new(-5, -5, -1, -1), bounds());
+        assert_eq!(complement.into_iter().count(), 16);
+    }
+}
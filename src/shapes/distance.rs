@@ -0,0 +1,398 @@
+use core::ops::RangeInclusive;
+
+use coord_2d::Coord;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// How the distance between two cells is measured
+pub enum DistanceMetric {
+    /// Diagonal steps cost the same as orthogonal ones
+    #[default]
+    Chebyshev,
+    /// Only orthogonal steps are allowed
+    Manhattan,
+    /// The square of the straight line distance
+    EuclideanSquared,
+    /// Diagonal steps cost √2
+    Octile,
+}
+
+impl DistanceMetric {
+    /// Returns the distance between two positions
+    pub fn distance(self, from: Coord, to: Coord) -> f32 {
+        self.length(from.x.abs_diff(to.x), from.y.abs_diff(to.y))
+    }
+
+    /// Determine if `a` is closer to `position` than `b`
+    ///
+    /// Ties are broken by the straight line distance, then by the lowest row and then the lowest
+    /// column, so the same cell is always picked.
+    pub fn is_closer(self, position: Coord, a: Coord, b: Coord) -> bool {
+        let key = |coord: Coord| {
+            // Squared in u64 so cells far apart don't overflow
+            let (dx, dy) = (
+                position.x.abs_diff(coord.x) as u64,
+                position.y.abs_diff(coord.y) as u64,
+            );
+            (self.distance(position, coord), dx * dx + dy * dy)
+        };
+        let (a_key, b_key) = (key(a), key(b));
+        a_key
+            .0
+            .total_cmp(&b_key.0)
+            .then(a_key.1.cmp(&b_key.1))
+            .then(a.y.cmp(&b.y))
+            .then(a.x.cmp(&b.x))
+            .is_lt()
+    }
+
+    /// Returns the closest of `coords` to `position`, see [`DistanceMetric::is_closer`]
+    pub fn closest(
+        self,
+        position: Coord,
+        coords: impl IntoIterator<Item = Coord>,
+    ) -> Option<Coord> {
+        coords
+            .into_iter()
+            .fold(None, |closest, coord| match closest {
+                Some(closest) if !self.is_closer(position, coord, closest) => Some(closest),
+                _ => Some(coord),
+            })
+    }
+
+    /// Returns the closest of the cells found along `range`, see [`DistanceMetric::is_closer`]
+    ///
+    /// `bound` gives the columns and rows every cell of `cells(i)` is at least away from
+    /// `position`, and must be convex over `range`. Rather than checking all of `range`, this
+    /// jumps to where the bound is smallest and only checks outwards while a cell could still be
+    /// closer, so few indices are checked unless the bound is flat over a long stretch.
+    pub(crate) fn closest_along<I: IntoIterator<Item = Coord>>(
+        self,
+        position: Coord,
+        range: RangeInclusive<i64>,
+        bound: impl Fn(i64) -> (f64, f64),
+        cells: impl Fn(i64) -> I,
+    ) -> Option<Coord> {
+        // The bound of the metric and of the tie break, both convex as `bound` is
+        let key = |i: i64| {
+            let (dx, dy) = bound(i);
+            (self.length_f64(dx, dy), dx * dx + dy * dy)
+        };
+
+        // Ternary search for the smallest key, which only levels off at its minimum
+        let (mut low, mut high) = (*range.start(), *range.end());
+        while high - low > 2 {
+            let third = (high - low) / 3;
+            let (a, b) = (low + third, high - third);
+            match key(a).partial_cmp(&key(b)) {
+                Some(core::cmp::Ordering::Less) => high = b - 1,
+                Some(core::cmp::Ordering::Greater) => low = a + 1,
+                _ => (low, high) = (a, b),
+            }
+        }
+        let start = (low..=high).min_by(|&a, &b| {
+            key(a)
+                .partial_cmp(&key(b))
+                .unwrap_or(core::cmp::Ordering::Equal)
+        })?;
+
+        let mut closest = None;
+        let mut check = |i: i64| {
+            // Past the smallest key the bound only grows, so the first index which can't beat
+            // the closest cell ends the search
+            if let Some(closest) = closest {
+                if self.beyond(position, key(i), closest) {
+                    return false;
+                }
+            }
+            for coord in cells(i) {
+                if closest.is_none_or(|closest| self.is_closer(position, coord, closest)) {
+                    closest = Some(coord);
+                }
+            }
+            true
+        };
+        check(start);
+        for i in (*range.start()..start).rev() {
+            if !check(i) {
+                break;
+            }
+        }
+        for i in start + 1..=*range.end() {
+            if !check(i) {
+                break;
+            }
+        }
+        closest
+    }
+
+    /// Determine if every cell with the bound `key` is further from `position` than `closest`
+    fn beyond(self, position: Coord, key: (f64, f64), closest: Coord) -> bool {
+        let (dx, dy) = (
+            position.x.abs_diff(closest.x) as f64,
+            position.y.abs_diff(closest.y) as f64,
+        );
+        let (distance, squared) = (self.distance(position, closest) as f64, dx * dx + dy * dy);
+
+        // Leave room for the rounding of both sides
+        let margin = |value: f64| value * 1e-6 + 1e-6;
+        if key.0 > distance + margin(distance) {
+            true
+        } else if key.0 < distance - margin(distance) {
+            false
+        } else {
+            key.1 > squared + margin(squared)
+        }
+    }
+
+    /// Returns the distance covered by moving `dx` columns and `dy` rows
+    pub fn length(self, dx: u32, dy: u32) -> f32 {
+        let (dx, dy) = (dx as f32, dy as f32);
+        match self {
+            DistanceMetric::Chebyshev => dx.max(dy),
+            DistanceMetric::Manhattan => dx + dy,
+            DistanceMetric::EuclideanSquared => dx * dx + dy * dy,
+            DistanceMetric::Octile => dx.max(dy) + (core::f32::consts::SQRT_2 - 1.0) * dx.min(dy),
+        }
+    }
+
+    /// Returns the distance covered by moving part way across cells
+    fn length_f64(self, dx: f64, dy: f64) -> f64 {
+        match self {
+            DistanceMetric::Chebyshev => dx.max(dy),
+            DistanceMetric::Manhattan => dx + dy,
+            DistanceMetric::EuclideanSquared => dx * dx + dy * dy,
+            DistanceMetric::Octile => dx.max(dy) + (core::f64::consts::SQRT_2 - 1.0) * dx.min(dy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Complement, Line, Rectangle, Shape};
+    use coord_2d::Size;
+
+    const METRICS: [DistanceMetric; 4] = [
+        DistanceMetric::Chebyshev,
+        DistanceMetric::Manhattan,
+        DistanceMetric::EuclideanSquared,
+        DistanceMetric::Octile,
+    ];
+
+    fn brute_force(shape: impl Shape, position: Coord, metric: DistanceMetric) -> f32 {
+        let mut distance = f32::INFINITY;
+        shape.for_each(|coord| distance = distance.min(metric.distance(position, coord)));
+        distance
+    }
+
+    fn cells(shape: impl Shape) -> Vec<Coord> {
+        let mut cells = Vec::new();
+        shape.for_each(|coord| cells.push(coord));
+        cells
+    }
+
+    fn check(shape: impl Shape) {
+        for metric in METRICS {
+            for position in shape.aabb().inflate(4, 3) {
+                assert_eq!(
+                    shape.closest_cell(position, metric),
+                    metric.closest(position, cells(shape)),
+                    "{:?} {:?} {:?}",
+                    shape,
+                    position,
+                    metric
+                );
+                assert_eq!(
+                    shape.distance(position, metric),
+                    brute_force(shape, position, metric),
+                    "{:?} {:?} {:?}",
+                    shape,
+                    position,
+                    metric
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance() {
+        let from = Coord::new(1, -2);
+        let to = Coord::new(-3, 1);
+        assert_eq!(DistanceMetric::Chebyshev.distance(from, to), 4.0);
+        assert_eq!(DistanceMetric::Manhattan.distance(from, to), 7.0);
+        assert_eq!(DistanceMetric::EuclideanSquared.distance(from, to), 25.0);
+        assert_eq!(
+            DistanceMetric::Octile.distance(from, to),
+            1.0 + 3.0 * std::f32::consts::SQRT_2
+        );
+    }
+
+    #[test]
+    fn test_is_closer() {
+        let position = Coord::new(0, 0);
+        let metric = DistanceMetric::Chebyshev;
+        assert!(metric.is_closer(position, Coord::new(1, 1), Coord::new(2, 0)));
+        assert!(metric.is_closer(position, Coord::new(2, 0), Coord::new(2, 1)));
+        assert!(metric.is_closer(position, Coord::new(0, -2), Coord::new(-2, 0)));
+        assert!(metric.is_closer(position, Coord::new(-2, 0), Coord::new(2, 0)));
+        assert!(!metric.is_closer(position, Coord::new(2, 0), Coord::new(2, 0)));
+
+        // Far enough apart to overflow squaring in i32
+        let position = Coord::new(50000, 3);
+        let metric = DistanceMetric::Manhattan;
+        assert!(metric.is_closer(position, Coord::new(5, 0), Coord::new(4, 1)));
+        assert_eq!(
+            Circle::new(Coord::new(0, 0), 5).distance(position, metric),
+            49996.0
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let empty = Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3));
+        for metric in METRICS {
+            assert_eq!(empty.closest_cell(Coord::new(1, 1), metric), None);
+            assert_eq!(empty.distance(Coord::new(1, 1), metric), f32::INFINITY);
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        check(Rectangle::new(0, 0, 0, 0));
+        check(Rectangle::new(-2, 1, 3, 4));
+        check(Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3)));
+        check(Rectangle::from_size(Coord::new(2, -1), Size::new(4, 0)));
+    }
+
+    #[test]
+    fn test_rectangle_border() {
+        for rectangle in [
+            Rectangle::new(0, 0, 0, 0),
+            Rectangle::new(0, 0, 1, 5),
+            Rectangle::new(-3, 1, 4, 7),
+            Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3)),
+        ] {
+            for metric in METRICS {
+                for position in rectangle.inflate(3, 3) {
+                    assert_eq!(
+                        rectangle.closest_border_cell(position, metric),
+                        metric.closest(position, rectangle.border_iter()),
+                        "{:?} {:?} {:?}",
+                        rectangle,
+                        position,
+                        metric
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_circle() {
+        for radius in 0..8 {
+            check(Circle::new(Coord::new(1, -1), radius));
+        }
+    }
+
+    #[test]
+    fn test_circle_circumference() {
+        for radius in 0..8 {
+            let circle = Circle::new(Coord::new(1, -1), radius);
+            for metric in METRICS {
+                for position in circle.aabb().inflate(3, 3) {
+                    assert_eq!(
+                        Some(circle.closest_circumference_cell(position, metric)),
+                        metric.closest(position, circle.circumference_iter()),
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        let range = Rectangle::new(-3, -3, 3, 3);
+        for end in range {
+            check(Line::new(Coord::new(0, 0), end));
+            check(Line::new(Coord::new(-1, 2), end));
+        }
+        check(Line::new(Coord::new(-9, 4), Coord::new(12, -3)));
+    }
+
+    #[test]
+    fn test_far() {
+        // Positions far from the shape, where whole stretches of it are nearly as close
+        let positions = Rectangle::new(-150, -150, 150, 150);
+        for shape in [
+            Line::new(Coord::new(-40, 7), Coord::new(55, -31)),
+            Line::new(Coord::new(3, -60), Coord::new(-5, 70)),
+            Line::new(Coord::new(-50, 50), Coord::new(50, -50)),
+        ] {
+            let cells = cells(shape);
+            for metric in METRICS {
+                for position in positions.into_iter().step_by(37) {
+                    assert_eq!(
+                        shape.closest_cell(position, metric),
+                        metric.closest(position, cells.iter().copied()),
+                        "{:?} {:?} {:?}",
+                        shape,
+                        position,
+                        metric
+                    );
+                }
+            }
+        }
+
+        let circle = Circle::new(Coord::new(4, -9), 23);
+        let cells = cells(circle);
+        for metric in METRICS {
+            for position in positions.into_iter().step_by(37) {
+                assert_eq!(
+                    circle.closest_cell(position, metric),
+                    metric.closest(position, cells.iter().copied()),
+                    "{:?} {:?} {:?}",
+                    circle,
+                    position,
+                    metric
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_circle_circumference_far() {
+        let circle = Circle::new(Coord::new(4, -9), 23);
+        for metric in METRICS {
+            for position in Rectangle::new(-150, -150, 150, 150).into_iter().step_by(37) {
+                assert_eq!(
+                    Some(circle.closest_circumference_cell(position, metric)),
+                    metric.closest(position, circle.circumference_iter()),
+                    "{:?} {:?}",
+                    position,
+                    metric
+                );
+            }
+            // Inside the circle
+            for position in circle.into_iter().step_by(7) {
+                assert_eq!(
+                    Some(circle.closest_circumference_cell(position, metric)),
+                    metric.closest(position, circle.circumference_iter()),
+                    "{:?} {:?}",
+                    position,
+                    metric
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback() {
+        check(Complement::new(
+            Circle::new(Coord::new(2, 2), 2),
+            Rectangle::new(0, 0, 4, 4),
+        ));
+    }
+}
@@ -0,0 +1,312 @@
+use coord_2d::Coord;
+
+use super::{Circle, Clipped, Complement, Line, Outline, Rectangle, Shape};
+
+/// Cell level intersection tests between two shapes
+///
+/// The provided methods check every cell where the bounding boxes of the shapes overlap, so
+/// `impl Intersects<Other> for MyShape {}` is enough for any shape. The built in shapes override
+/// them with faster versions where possible.
+pub trait Intersects<S: Shape>: Shape {
+    /// Determine if any cell is part of both shapes
+    fn intersects(self, other: S) -> bool {
+        match self.aabb().intersection(other.aabb()) {
+            Some(overlap) => overlap
+                .into_iter()
+                .any(|coord| self.contains(coord) && other.contains(coord)),
+            None => false,
+        }
+    }
+
+    /// Returns the number of cells which are part of both shapes
+    fn overlap_count(self, other: S) -> usize {
+        match self.aabb().intersection(other.aabb()) {
+            Some(overlap) => overlap
+                .into_iter()
+                .filter(|&coord| self.contains(coord) && other.contains(coord))
+                .count(),
+            None => 0,
+        }
+    }
+}
+
+/// Returns the number of cells shared by the columns `a` and `b`
+fn span_overlap(a: (i32, i32), b: (i32, i32)) -> usize {
+    (a.1.min(b.1) - a.0.max(b.0) + 1).max(0) as usize
+}
+
+/// Returns the shared columns of `circle` with `columns` for each row of `overlap`
+fn circle_spans(
+    circle: Circle,
+    overlap: Rectangle,
+    columns: impl Fn(i32) -> Option<(i32, i32)>,
+) -> impl Iterator<Item = usize> {
+    (overlap.bottom()..=overlap.top()).map(move |y| match (circle.row_span(y), columns(y)) {
+        (Some(a), Some(b)) => span_overlap(a, b),
+        _ => 0,
+    })
+}
+
+/// Returns the cells of `line` which are also part of `other`
+fn line_cells(line: Line, other: impl Shape) -> impl Iterator<Item = Coord> {
+    line.steps_within(other.aabb())
+        .into_iter()
+        .flat_map(|(first, last)| first..=last)
+        .map(move |step| line.step_coord(step))
+        .filter(move |&coord| other.contains(coord))
+}
+
+// Rectangle
+impl Intersects<Rectangle> for Rectangle {
+    fn intersects(self, other: Rectangle) -> bool {
+        !self.size.is_empty()
+            && !other.size.is_empty()
+            && self.left() <= other.right()
+            && self.right() >= other.left()
+            && self.bottom() <= other.top()
+            && self.top() >= other.bottom()
+    }
+
+    fn overlap_count(self, other: Rectangle) -> usize {
+        self.intersection(other)
+            .map_or(0, |overlap| overlap.cell_count())
+    }
+}
+
+impl Intersects<Circle> for Rectangle {
+    fn intersects(self, other: Circle) -> bool {
+        // The circle fills in towards its center, so the closest cell is enough
+        self.clamp_coord(other.center)
+            .is_some_and(|coord| other.contains(coord))
+    }
+
+    fn overlap_count(self, other: Circle) -> usize {
+        match self.intersection(other.aabb()) {
+            Some(overlap) => {
+                circle_spans(other, overlap, |_| Some((self.left(), self.right()))).sum()
+            }
+            None => 0,
+        }
+    }
+}
+
+impl Intersects<Line> for Rectangle {
+    fn intersects(self, other: Line) -> bool {
+        other.steps_within(self).is_some()
+    }
+
+    fn overlap_count(self, other: Line) -> usize {
+        other
+            .steps_within(self)
+            .map_or(0, |(first, last)| (last - first) as usize + 1)
+    }
+}
+
+// Circle
+impl Intersects<Rectangle> for Circle {
+    fn intersects(self, other: Rectangle) -> bool {
+        Intersects::intersects(other, self)
+    }
+
+    fn overlap_count(self, other: Rectangle) -> usize {
+        other.overlap_count(self)
+    }
+}
+
+impl Intersects<Circle> for Circle {
+    fn intersects(self, other: Circle) -> bool {
+        match self.aabb().intersection(other.aabb()) {
+            Some(overlap) => {
+                circle_spans(self, overlap, |y| other.row_span(y)).any(|count| count > 0)
+            }
+            None => false,
+        }
+    }
+
+    fn overlap_count(self, other: Circle) -> usize {
+        match self.aabb().intersection(other.aabb()) {
+            Some(overlap) => circle_spans(self, overlap, |y| other.row_span(y)).sum(),
+            None => 0,
+        }
+    }
+}
+
+impl Intersects<Line> for Circle {
+    fn intersects(self, other: Line) -> bool {
+        line_cells(other, self).next().is_some()
+    }
+
+    fn overlap_count(self, other: Line) -> usize {
+        line_cells(other, self).count()
+    }
+}
+
+// Line
+impl Intersects<Rectangle> for Line {
+    fn intersects(self, other: Rectangle) -> bool {
+        Intersects::intersects(other, self)
+    }
+
+    fn overlap_count(self, other: Rectangle) -> usize {
+        other.overlap_count(self)
+    }
+}
+
+impl Intersects<Circle> for Line {
+    fn intersects(self, other: Circle) -> bool {
+        other.intersects(self)
+    }
+
+    fn overlap_count(self, other: Circle) -> usize {
+        other.overlap_count(self)
+    }
+}
+
+impl Intersects<Line> for Line {
+    fn intersects(self, other: Line) -> bool {
+        line_cells(self, other).next().is_some()
+    }
+
+    fn overlap_count(self, other: Line) -> usize {
+        line_cells(self, other).count()
+    }
+}
+
+// Adapters
+impl<A: Shape, S: Shape> Intersects<S> for Outline<A> {}
+impl<A: Shape, S: Shape> Intersects<S> for Complement<A> {}
+impl<A: Shape, S: Shape> Intersects<S> for Clipped<A> {}
+
+impl<A: Shape> Intersects<Outline<A>> for Rectangle {}
+impl<A: Shape> Intersects<Outline<A>> for Circle {}
+impl<A: Shape> Intersects<Outline<A>> for Line {}
+impl<A: Shape> Intersects<Complement<A>> for Rectangle {}
+impl<A: Shape> Intersects<Complement<A>> for Circle {}
+impl<A: Shape> Intersects<Complement<A>> for Line {}
+impl<A: Shape> Intersects<Clipped<A>> for Rectangle {}
+impl<A: Shape> Intersects<Clipped<A>> for Circle {}
+impl<A: Shape> Intersects<Clipped<A>> for Line {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Connectivity, OutlineKind};
+    use coord_2d::Size;
+    use std::collections::HashSet;
+
+    fn brute_force(a: impl Shape, b: impl Shape) -> usize {
+        let mut cells = HashSet::new();
+        a.for_each(|coord| {
+            cells.insert(coord);
+        });
+        let mut count = 0;
+        b.for_each(|coord| {
+            if cells.remove(&coord) {
+                count += 1;
+            }
+        });
+        count
+    }
+
+    fn check<A: Intersects<B>, B: Shape>(a: A, b: B) {
+        let count = brute_force(a, b);
+        assert_eq!(a.overlap_count(b), count, "{:?} {:?}", a, b);
+        assert_eq!(a.intersects(b), count > 0, "{:?} {:?}", a, b);
+    }
+
+    fn rectangles() -> Vec<Rectangle> {
+        vec![
+            Rectangle::new(0, 0, 0, 0),
+            Rectangle::new(-3, -2, 4, 1),
+            Rectangle::new(2, 2, 9, 3),
+            Rectangle::new(-8, -8, -5, 6),
+            Rectangle::new(5, -9, 5, 9),
+            Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3)),
+        ]
+    }
+
+    fn circles() -> Vec<Circle> {
+        vec![
+            Circle::new(Coord::new(0, 0), 0),
+            Circle::new(Coord::new(0, 0), 3),
+            Circle::new(Coord::new(6, 4), 2),
+            Circle::new(Coord::new(-6, 1), 5),
+            Circle::new(Coord::new(9, -9), 4),
+        ]
+    }
+
+    fn lines() -> Vec<Line> {
+        vec![
+            Line::new(Coord::new(0, 0), Coord::new(0, 0)),
+            Line::new(Coord::new(-9, -3), Coord::new(9, 4)),
+            Line::new(Coord::new(4, 9), Coord::new(1, -9)),
+            Line::new(Coord::new(-7, 7), Coord::new(7, -7)),
+            Line::new(Coord::new(3, 2), Coord::new(-6, 2)),
+        ]
+    }
+
+    #[test]
+    fn test_rectangle() {
+        for a in rectangles() {
+            rectangles().into_iter().for_each(|b| check(a, b));
+            circles().into_iter().for_each(|b| check(a, b));
+            lines().into_iter().for_each(|b| check(a, b));
+        }
+    }
+
+    #[test]
+    fn test_circle() {
+        for a in circles() {
+            rectangles().into_iter().for_each(|b| check(a, b));
+            circles().into_iter().for_each(|b| check(a, b));
+            lines().into_iter().for_each(|b| check(a, b));
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        for a in lines() {
+            rectangles().into_iter().for_each(|b| check(a, b));
+            circles().into_iter().for_each(|b| check(a, b));
+            lines().into_iter().for_each(|b| check(a, b));
+        }
+    }
+
+    #[test]
+    fn test_line_rectangle_exhaustive() {
+        let range = Rectangle::new(-3, -3, 3, 3);
+        let rectangle = Rectangle::new(-1, 0, 2, 1);
+        for start in range {
+            for end in range {
+                check(rectangle, Line::new(start, end));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback() {
+        let outline =
+            Circle::new(Coord::new(0, 0), 4).outline(Connectivity::Four, OutlineKind::Outer);
+        for b in rectangles() {
+            check(outline, b);
+            check(b, outline);
+        }
+        let complement = Complement::new(Rectangle::new(0, 0, 3, 3), Rectangle::new(-2, -2, 5, 5));
+        for b in circles() {
+            check(complement, b);
+            check(b, complement);
+        }
+        let clipped = Clipped::new(
+            Line::new(Coord::new(-9, -6), Coord::new(9, 5)),
+            Rectangle::new(-4, -4, 3, 6),
+        );
+        for b in lines() {
+            check(clipped, b);
+            check(b, clipped);
+        }
+        for b in circles() {
+            check(clipped, b);
+            check(b, clipped);
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use coord_2d::Coord;
 
 use super::circle_circumference_iter_step::CircleCircumferenceIterStep;
@@ -7,7 +5,6 @@ use super::circle_circumference_iter_step::CircleCircumferenceIterStep;
 #[derive(Debug, Clone)]
 pub struct CircleCircumferenceIter {
     center: Coord,
-    discovered: HashSet<Coord>,
     d: i32,
     x: i32,
     y: i32,
@@ -18,7 +15,6 @@ impl CircleCircumferenceIter {
     pub fn new(center: Coord, radius: u32) -> Self {
         Self {
             center,
-            discovered: HashSet::new(),
             d: (5 - (radius as i32 * 4)) / 4,
             x: 0,
             y: radius as i32,
@@ -26,6 +22,27 @@ impl CircleCircumferenceIter {
         }
     }
 
+    /// Determine if the current step lands on a cell an earlier step of this position already gave
+    ///
+    /// Cells only repeat on the axes (`x == 0`), the diagonals (`x == y`) and when there is no
+    /// radius (`y == 0`), every other position of the midpoint algorithm gives eight distinct cells.
+    fn is_repeat(&self) -> bool {
+        let on_axis = self.x == 0;
+        let on_diagonal = self.x == self.y;
+        let at_center = self.y == 0;
+        match self.step {
+            CircleCircumferenceIterStep::Line1 => false,
+            CircleCircumferenceIterStep::Line2 => at_center,
+            CircleCircumferenceIterStep::Line3 => on_axis,
+            CircleCircumferenceIterStep::Line4 => on_axis || at_center,
+            CircleCircumferenceIterStep::Line5 => on_diagonal,
+            CircleCircumferenceIterStep::Line6 => on_diagonal || on_axis,
+            CircleCircumferenceIterStep::Line7 => on_diagonal || at_center,
+            CircleCircumferenceIterStep::Line8 => on_diagonal || on_axis || at_center,
+            CircleCircumferenceIterStep::Process | CircleCircumferenceIterStep::Finished => false,
+        }
+    }
+
     fn try_next_point(&mut self) -> Option<Coord> {
         let c = match self.step {
             CircleCircumferenceIterStep::Line1 => {
@@ -87,19 +104,11 @@ impl Iterator for CircleCircumferenceIter {
             return None;
         }
 
-        // Get the next point
-        if let Some(c) = self.try_next_point() {
-            // Make sure it's unique
-            if self.discovered.insert(c) {
-                // It's new return it
-                return Some(c);
-            } else {
-                // It's old, try the next one
-                return self.next();
-            }
+        // Skip the steps which repeat a cell
+        while self.is_repeat() {
+            self.step = self.step.next();
         }
 
-        // Couldn't get the next point
-        None
+        self.try_next_point()
     }
 }
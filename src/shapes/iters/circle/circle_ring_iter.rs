@@ -0,0 +1,121 @@
+use coord_2d::Coord;
+
+use super::midpoint;
+use crate::shapes::RingKind;
+
+#[derive(Debug, Clone)]
+pub struct CircleRingIter {
+    center: Coord,
+    kind: RingKind,
+    max_ring: u32,
+    clip_radius: Option<u32>,
+    ring: u32,
+    y: i32,
+    x: i32,
+    right: i32,
+    next_span: Option<(i32, i32)>,
+}
+
+impl CircleRingIter {
+    /// Creates an iterator over every ring around `center` up to and including `max_radius`
+    pub fn new(center: Coord, max_radius: u32, kind: RingKind) -> Self {
+        Self {
+            center,
+            kind,
+            max_ring: max_radius,
+            clip_radius: None,
+            ring: 0,
+            // Start on the only row of the first ring
+            y: 0,
+            x: 0,
+            right: 0,
+            next_span: None,
+        }
+    }
+
+    /// Creates an iterator over the rings around the center of a circle, only keeping the cells
+    /// inside of it
+    pub(crate) fn new_within(center: Coord, radius: u32, kind: RingKind) -> Self {
+        // Diagonal cells of the circle are further away when only moving orthogonally
+        let max_ring = match kind {
+            RingKind::Manhattan => radius.saturating_mul(2),
+            RingKind::Euclidean | RingKind::Chebyshev => radius,
+        };
+
+        Self {
+            max_ring,
+            clip_radius: Some(radius),
+            ..Self::new(center, max_ring, kind)
+        }
+    }
+
+    /// Returns the number of cells either side of the center in row `y` of the area covered by
+    /// the rings up to `ring`
+    fn half_width(&self, ring: u32, y: u32) -> Option<u32> {
+        if y > ring {
+            return None;
+        }
+
+        match self.kind {
+            RingKind::Euclidean => midpoint::half_width(ring, y),
+            RingKind::Chebyshev => Some(ring),
+            RingKind::Manhattan => Some(ring - y),
+        }
+    }
+
+    /// Moves on to the next row, or the first row of the next ring
+    fn next_row(&mut self) -> bool {
+        self.y += 1;
+        if self.y > self.ring as i32 {
+            if self.ring >= self.max_ring {
+                return false;
+            }
+            self.ring += 1;
+            self.y = -(self.ring as i32);
+        }
+
+        // The ring is what's left of the row once the rings inside of it are taken out
+        let y = self.y.unsigned_abs();
+        let outer = self.half_width(self.ring, y).unwrap_or(0) as i32;
+        match self.half_width(self.ring - 1, y) {
+            Some(inner) => {
+                let inner = inner as i32;
+                self.x = -outer;
+                self.right = -inner - 1;
+                self.next_span = Some((inner + 1, outer));
+            }
+            None => {
+                self.x = -outer;
+                self.right = outer;
+                self.next_span = None;
+            }
+        }
+        true
+    }
+}
+
+impl Iterator for CircleRingIter {
+    type Item = (u32, Coord);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.x <= self.right {
+                let (x, y) = (self.x, self.y);
+                self.x += 1;
+
+                if let Some(radius) = self.clip_radius {
+                    if !midpoint::contains(radius, x, y) {
+                        continue;
+                    }
+                }
+                return Some((self.ring, Coord::new(self.center.x + x, self.center.y + y)));
+            }
+
+            if let Some((left, right)) = self.next_span.take() {
+                self.x = left;
+                self.right = right;
+            } else if !self.next_row() {
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Closed forms of the midpoint circle algorithm used by `CircleIter`
+//!
+//! Stepping the algorithm keeps `d == x² + y² + y + c` where `c` only depends on the radius, so the
+//! `y` reached for each `x` can be solved for directly rather than walking every step.
+
+/// The constant part of the decision variable for `radius`
+#[inline]
+fn offset(radius: u32) -> i128 {
+    let radius = radius as i128;
+    // The initial decision variable, rounded towards zero like `CircleIter`
+    let d = (5 - radius * 4) / 4;
+    radius * radius + radius - d
+}
+
+/// Returns the furthest cell from the center along column `x` of the first octant
+///
+/// This is the `y` the midpoint algorithm reaches for `x`, it is only part of the circle while
+/// `x <= y`.
+pub fn height(radius: u32, x: u32) -> Option<u32> {
+    if x == 0 {
+        return Some(radius);
+    }
+
+    // Largest y where y² + y < offset - (x - 1)²
+    let x = x as i128 - 1;
+    let k = offset(radius) - x * x;
+    if k <= 0 {
+        return None;
+    }
+
+    let y = ((4 * k - 3) as u128).isqrt() as i128;
+    Some(((y - 1) / 2).min(radius as i128) as u32)
+}
+
+/// Returns the number of cells either side of the center in row (or column) `y` of the circle
+pub fn half_width(radius: u32, y: u32) -> Option<u32> {
+    if y > radius {
+        return None;
+    }
+
+    match height(radius, y) {
+        // The row reaches past the diagonal
+        Some(x) if x >= y => Some(x),
+        // The row stops before the diagonal, find the last column still reaching it
+        _ => {
+            let y = y as i128;
+            let m = offset(radius) - y * y - y;
+            if m <= 0 {
+                return Some(0);
+            }
+            Some((((m - 1) as u128).isqrt() + 1) as u32)
+        }
+    }
+}
+
+/// Returns a bound on `half_width` which is concave over the rows of the circle
+pub fn max_half_width(radius: u32, y: u64) -> f64 {
+    // By `height` every cell has x² < offset - (y - 1)² or (x - 1)² < offset - y²
+    let y = y.saturating_sub(1) as i128;
+    let k = offset(radius) - y * y;
+    if k <= 0 {
+        return 1.0;
+    }
+
+    // `f64::sqrt` needs `std`, so Newton's method closes in on the root from above
+    let k = k as f64;
+    let mut root = ((k as u128).isqrt() + 1) as f64;
+    loop {
+        let next = (root + k / root) / 2.0;
+        if next >= root {
+            return 1.0 + root;
+        }
+        root = next;
+    }
+}
+
+/// Determine if an offset from the center is inside a circle of `radius`
+pub fn contains(radius: u32, dx: i32, dy: i32) -> bool {
+    let (dx, dy) = (dx.unsigned_abs(), dy.unsigned_abs());
+    let (near, far) = (dx.min(dy), dx.max(dy));
+    height(radius, near).is_some_and(|y| far <= y)
+}
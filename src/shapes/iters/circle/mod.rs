@@ -1,7 +1,11 @@
+pub(crate) mod midpoint;
+
 mod circle_iter;
-pub(crate) mod circle_iter_step;
 pub use self::circle_iter::*;
 
+mod circle_ring_iter;
+pub use self::circle_ring_iter::*;
+
 mod circle_circumference_iter;
 pub(crate) mod circle_circumference_iter_step;
 pub use self::circle_circumference_iter::*;
@@ -4,12 +4,13 @@ use super::octant::Octant;
 
 #[derive(Debug, Clone)]
 pub struct LineBresenhamIter {
-    abs_x: i32,
-    abs_y: i32,
-    end_x: i32,
-    delta_step: i32,
-    delta_x: i32,
-    delta_y: i32,
+    origin: (i64, i64),
+    abs_x: i64,
+    abs_y: i64,
+    end_x: i64,
+    delta_step: i64,
+    delta_x: i64,
+    delta_y: i64,
     octant: Octant,
 }
 
@@ -17,13 +18,17 @@ impl LineBresenhamIter {
     pub fn new(start: Coord, end: Coord) -> Self {
         let octant = Octant::new(start, end);
 
+        // Walked in i64 so lines over 2³¹ cells long don't overflow
         let start_offset = octant.to_offset(start);
+        let start_offset = (start_offset.0 as i64, start_offset.1 as i64);
         let end_offset = octant.to_offset(end);
+        let end_offset = (end_offset.0 as i64, end_offset.1 as i64);
 
         let delta_x = end_offset.0 - start_offset.0;
         let delta_y = end_offset.1 - start_offset.1;
 
         Self {
+            origin: start_offset,
             abs_x: start_offset.0,
             abs_y: start_offset.1,
             end_x: end_offset.0,
@@ -34,6 +39,34 @@ impl LineBresenhamIter {
         }
     }
 
+    /// Creates an iterator over only the cells from step `first` to step `last` of the line
+    pub(crate) fn new_between(start: Coord, end: Coord, first: u32, last: u32) -> Self {
+        let mut iter = Self::new(start, end);
+        iter.end_x = iter.end_x.min(iter.origin.0 + last as i64);
+        iter.jump_to(first as i64);
+        iter
+    }
+
+    /// Get the rise of the line `step` cells from its start
+    fn rise(&self, step: i64) -> i64 {
+        if self.delta_x == 0 {
+            0
+        } else {
+            (step as i128 * self.delta_y as i128 / self.delta_x as i128) as i64
+        }
+    }
+
+    /// Move straight to the cell `step` cells from the start of the line
+    fn jump_to(&mut self, step: i64) {
+        let rise = self.rise(step);
+
+        // Pick up the error term where it would be after `step` steps
+        self.delta_step = ((step as i128 + 1) * self.delta_y as i128
+            - (rise as i128 + 1) * self.delta_x as i128) as i64;
+        self.abs_x = self.origin.0 + step;
+        self.abs_y = self.origin.1 + rise;
+    }
+
     pub fn advance(&mut self) -> Coord {
         let current_point = (self.abs_x, self.abs_y);
         if self.delta_step >= 0 {
@@ -45,7 +78,21 @@ impl LineBresenhamIter {
 
         self.abs_x += 1;
 
-        self.octant.from_offset(current_point)
+        self.octant
+            .from_offset((current_point.0 as i32, current_point.1 as i32))
+    }
+
+    /// Get the Coord of the last cell still to come and drop it
+    fn retreat(&mut self) -> Coord {
+        let current_point = (
+            self.end_x,
+            self.origin.1 + self.rise(self.end_x - self.origin.0),
+        );
+
+        self.end_x -= 1;
+
+        self.octant
+            .from_offset((current_point.0 as i32, current_point.1 as i32))
     }
 }
 
@@ -59,4 +106,42 @@ impl Iterator for LineBresenhamIter {
             Some(self.advance())
         }
     }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.abs_x = self.end_x + 1;
+            return None;
+        }
+
+        // Skip the cells in between without walking them
+        self.jump_to(self.abs_x - self.origin.0 + n as i64);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end_x - self.abs_x + 1).max(0) as usize;
+        (len, Some(len))
+    }
 }
+
+impl DoubleEndedIterator for LineBresenhamIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.abs_x > self.end_x {
+            None
+        } else {
+            Some(self.retreat())
+        }
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.end_x = self.abs_x - 1;
+            return None;
+        }
+
+        self.end_x -= n as i64;
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for LineBresenhamIter {}
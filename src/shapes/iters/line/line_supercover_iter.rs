@@ -0,0 +1,68 @@
+use coord_2d::Coord;
+
+#[derive(Debug, Clone)]
+/// Iterates over every cell the line between the centers of two cells passes through
+///
+/// Where the line passes exactly through the corner of a cell both cells beside the corner are
+/// included, the horizontal neighbour first.
+pub struct LineSupercoverIter {
+    current: Coord,
+    step: (i32, i32),
+    delta: (i64, i64),
+    // Steps taken along each axis so far
+    taken: (i64, i64),
+    // Cells left over from passing a corner
+    corner: [Option<Coord>; 2],
+    started: bool,
+}
+
+impl LineSupercoverIter {
+    pub fn new(start: Coord, end: Coord) -> Self {
+        let delta = end - start;
+        Self {
+            current: start,
+            step: (delta.x.signum(), delta.y.signum()),
+            delta: ((delta.x as i64).abs(), (delta.y as i64).abs()),
+            taken: (0, 0),
+            corner: [None, None],
+            started: false,
+        }
+    }
+}
+
+impl Iterator for LineSupercoverIter {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(coord) = self.corner[0].take().or_else(|| self.corner[1].take()) {
+            return Some(coord);
+        }
+        if !self.started {
+            self.started = true;
+            return Some(self.current);
+        }
+        if self.taken.0 >= self.delta.0 && self.taken.1 >= self.delta.1 {
+            return None;
+        }
+
+        // Compares where the line crosses the next vertical and horizontal cell edges
+        let decision =
+            (1 + 2 * self.taken.0) * self.delta.1 - (1 + 2 * self.taken.1) * self.delta.0;
+        if decision == 0 {
+            let horizontal = Coord::new(self.current.x + self.step.0, self.current.y);
+            let vertical = Coord::new(self.current.x, self.current.y + self.step.1);
+            self.current = Coord::new(self.current.x + self.step.0, self.current.y + self.step.1);
+            self.taken = (self.taken.0 + 1, self.taken.1 + 1);
+            self.corner = [Some(vertical), Some(self.current)];
+            return Some(horizontal);
+        }
+        if decision < 0 {
+            self.current.x += self.step.0;
+            self.taken.0 += 1;
+        } else {
+            self.current.y += self.step.1;
+            self.taken.1 += 1;
+        }
+        Some(self.current)
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::RangeInclusive;
+use core::ops::RangeInclusive;
 
 use coord_2d::Coord;
 
@@ -14,8 +14,8 @@ impl LineTunnelVerticalHorizontalIter {
     pub fn new(start: Coord, end: Coord) -> Self {
         Self {
             start,
-            x: (start.x..=end.x).into_iter(),
-            y: (start.y..=end.y).into_iter(),
+            x: (start.x..=end.x),
+            y: (start.y..=end.y),
             end,
         }
     }
@@ -34,3 +34,15 @@ impl Iterator for LineTunnelVerticalHorizontalIter {
         }
     }
 }
+
+impl DoubleEndedIterator for LineTunnelVerticalHorizontalIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(x) = self.x.next_back() {
+            Some(Coord::new(x, self.end.y))
+        } else if let Some(y) = self.y.next_back() {
+            Some(Coord::new(self.start.x, y))
+        } else {
+            None
+        }
+    }
+}
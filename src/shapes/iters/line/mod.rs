@@ -1,4 +1,5 @@
 pub(crate) mod octant;
+pub(crate) use self::octant::Octant;
 
 mod line_bresenham_iter;
 pub use self::line_bresenham_iter::*;
@@ -6,3 +7,5 @@ mod line_tunnel_horizontal_vertical;
 pub use self::line_tunnel_horizontal_vertical::*;
 mod line_tunnel_vertical_horizontal;
 pub use self::line_tunnel_vertical_horizontal::*;
+mod line_supercover_iter;
+pub use self::line_supercover_iter::*;
@@ -1,15 +1,14 @@
 use coord_2d::Coord;
 
 /// An octant
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Octant(pub u8);
 
 impl Octant {
     /// adapted from <http://codereview.stackexchange.com/a/95551>
     /// converts a `Coord` into a coordinate relative `Octant(0)` offset
-
     #[inline]
-    pub fn to_offset(&self, position: Coord) -> (i32, i32) {
+    pub fn to_offset(self, position: Coord) -> (i32, i32) {
         match self.0 {
             0 => (position.x, position.y),
             1 => (position.y, position.x),
@@ -26,7 +25,7 @@ impl Octant {
     /// converts from a `Octant(0)` relative coordinate into a `Coord`
     #[inline]
     #[allow(clippy::wrong_self_convention)]
-    pub fn from_offset(&self, offset: (i32, i32)) -> Coord {
+    pub fn from_offset(self, offset: (i32, i32)) -> Coord {
         let p = match self.0 {
             0 => (offset.0, offset.1),
             1 => (offset.1, offset.0),
@@ -48,8 +47,9 @@ impl Octant {
         let start = position;
         let end = other;
 
-        let mut dx = end.x - start.x;
-        let mut dy = end.y - start.y;
+        // Subtracted in i64 so points far apart don't overflow
+        let mut dx = end.x as i64 - start.x as i64;
+        let mut dy = end.y as i64 - start.y as i64;
         let mut octant = 0;
         if dy < 0 {
             dx = -dx;
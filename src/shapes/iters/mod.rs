@@ -1,4 +1,5 @@
 mod circle;
+pub(crate) use self::circle::midpoint;
 pub use self::circle::*;
 
 mod line;
@@ -6,3 +7,6 @@ pub use self::line::*;
 
 mod rectangle;
 pub use self::rectangle::*;
+
+mod shape;
+pub use self::shape::*;
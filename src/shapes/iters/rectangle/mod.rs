@@ -3,3 +3,6 @@ pub use self::rectangle_border_iter::*;
 
 mod rectangle_iter;
 pub use self::rectangle_iter::*;
+
+mod rectangle_order_iter;
+pub use self::rectangle_order_iter::*;
@@ -2,52 +2,86 @@ use coord_2d::{Coord, Size};
 
 #[derive(Debug, Clone)]
 pub struct RectangleBorderIter {
-    offset: Coord,
-    max_offset: Coord,
     position: Coord,
+    width: usize,
+    height: usize,
+    index: usize,
+    end: usize,
 }
 
 impl RectangleBorderIter {
     pub fn new(position: Coord, size: Size) -> Self {
-        let max_offset = Coord::new(
-            position.x + size.width() as i32,
-            position.y + size.height() as i32,
-        );
+        let (width, height) = (size.width() as usize, size.height() as usize);
+
+        // The bottom and top rows plus the sides of the rows in between
+        let end = match height {
+            _ if size.is_empty() => 0,
+            1 => width,
+            _ => 2 * width + (height - 2) * width.min(2),
+        };
 
         Self {
-            offset: Coord::new(0, 0),
-            max_offset,
             position,
+            width,
+            height,
+            index: 0,
+            end,
         }
     }
+
+    /// Get the Coord of the cell `index` cells along the border, row by row
+    fn coord(&self, index: usize) -> Coord {
+        let sides = self.width.min(2);
+        let middle = (self.height.saturating_sub(2)) * sides;
+
+        let (x, y) = if index < self.width {
+            // Bottom row
+            (index, 0)
+        } else if index - self.width < middle {
+            // Left or right side
+            let index = index - self.width;
+            let x = if index.is_multiple_of(sides) {
+                0
+            } else {
+                self.width - 1
+            };
+            (x, 1 + index / sides)
+        } else {
+            // Top row
+            (index - self.width - middle, self.height - 1)
+        };
+
+        Coord::new(self.position.x + x as i32, self.position.y + y as i32)
+    }
 }
 
 impl Iterator for RectangleBorderIter {
     type Item = Coord;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.offset.y >= self.max_offset.y {
+        if self.index >= self.end {
             return None;
         }
 
-        let p = self.offset;
+        let c = self.coord(self.index);
+        self.index += 1;
+        Some(c)
+    }
 
-        // Fill in top and bottom lines
-        if self.offset.y == 0 || self.offset.y == self.max_offset.y - 1 {
-            self.offset.x = self.offset.x + 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.index;
+        (len, Some(len))
+    }
+}
 
-            if self.offset.x >= self.max_offset.x {
-                self.offset.x = 0;
-                self.offset.y = self.offset.y + 1;
-            }
-        } else {
-            if self.offset.x == 0 {
-                self.offset.x = self.max_offset.x - 1;
-            } else {
-                self.offset.x = 0;
-                self.offset.y = self.offset.y + 1;
-            }
+impl DoubleEndedIterator for RectangleBorderIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
         }
 
-        Some(Coord::new(self.position.x + p.x, self.position.y + p.y))
+        self.end -= 1;
+        Some(self.coord(self.end))
     }
 }
+
+impl ExactSizeIterator for RectangleBorderIter {}
@@ -2,39 +2,60 @@ use coord_2d::{Coord, Size};
 
 #[derive(Debug, Clone)]
 pub struct RectangleIter {
-    offset: Coord,
-    max_offset: Coord,
     position: Coord,
+    width: usize,
+    index: usize,
+    end: usize,
 }
 
 impl RectangleIter {
     pub fn new(position: Coord, size: Size) -> Self {
-        let max_offset = Coord::new(size.width() as i32, size.height() as i32);
-
         Self {
-            offset: Coord::new(0, 0),
-            max_offset,
             position,
+            width: size.width() as usize,
+            index: 0,
+            // An empty rectangle has no cells to walk, multiplied as usize so large rectangles
+            // don't overflow
+            end: size.width() as usize * size.height() as usize,
         }
     }
+
+    /// Get the Coord of the cell `index` cells into the rectangle, row by row
+    fn coord(&self, index: usize) -> Coord {
+        Coord::new(
+            self.position.x + (index % self.width) as i32,
+            self.position.y + (index / self.width) as i32,
+        )
+    }
 }
 
 impl Iterator for RectangleIter {
     type Item = Coord;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.offset.y >= self.max_offset.y {
+        if self.index >= self.end {
             return None;
         }
 
-        let p = self.offset;
+        let c = self.coord(self.index);
+        self.index += 1;
+        Some(c)
+    }
 
-        self.offset.x = self.offset.x + 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.index;
+        (len, Some(len))
+    }
+}
 
-        if self.offset.x >= self.max_offset.x {
-            self.offset.x = 0;
-            self.offset.y = self.offset.y + 1;
+impl DoubleEndedIterator for RectangleIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
         }
 
-        Some(Coord::new(self.position.x + p.x, self.position.y + p.y))
+        self.end -= 1;
+        Some(self.coord(self.end))
     }
 }
+
+impl ExactSizeIterator for RectangleIter {}
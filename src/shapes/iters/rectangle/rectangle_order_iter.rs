@@ -0,0 +1,154 @@
+use coord_2d::{Coord, Size};
+
+use crate::shapes::TraversalOrder;
+
+#[derive(Debug, Clone)]
+pub struct RectangleOrderIter {
+    position: Coord,
+    width: u64,
+    height: u64,
+    order: TraversalOrder,
+    tile_size: u64,
+    index: u64,
+    end: u64,
+    remaining: u64,
+}
+
+/// Tiles are at most as wide as the largest `Size`, so a tile's area always fits in a u64
+const MAX_TILE_SIZE: u64 = 1 << 31;
+
+impl RectangleOrderIter {
+    pub fn new(position: Coord, size: Size, order: TraversalOrder) -> Self {
+        let (width, height) = (size.width() as u64, size.height() as u64);
+
+        // Curves fill square tiles laid along the longest side, so thin rectangles don't walk a
+        // square far bigger than themselves
+        let tile_size = width.min(height).next_power_of_two().min(MAX_TILE_SIZE);
+        let end = match order {
+            _ if size.is_empty() => 0,
+            TraversalOrder::ZOrder | TraversalOrder::Hilbert => {
+                let tiles = width.max(height).div_ceil(tile_size);
+                tiles.saturating_mul(tile_size * tile_size)
+            }
+            _ => width * height,
+        };
+
+        Self {
+            position,
+            width,
+            height,
+            order,
+            tile_size,
+            index: 0,
+            end,
+            remaining: width * height,
+        }
+    }
+
+    /// Get the offset of step `index` of the traversal, which may be outside of a rectangle
+    /// traversed by a curve
+    fn offset(&self, index: u64) -> (u64, u64) {
+        match self.order {
+            TraversalOrder::RowMajor => (index % self.width, index / self.width),
+            TraversalOrder::ColumnMajor => (index / self.height, index % self.height),
+            TraversalOrder::Reversed => {
+                let index = self.width * self.height - 1 - index;
+                (index % self.width, index / self.width)
+            }
+            TraversalOrder::Serpentine => {
+                let (x, y) = (index % self.width, index / self.width);
+                // Every other row runs right to left
+                if y % 2 == 0 {
+                    (x, y)
+                } else {
+                    (self.width - 1 - x, y)
+                }
+            }
+            TraversalOrder::ZOrder | TraversalOrder::Hilbert => {
+                let area = self.tile_size * self.tile_size;
+                let (tile, index) = (index / area, index % area);
+                let (x, y) = match self.order {
+                    TraversalOrder::ZOrder => z_order(index),
+                    _ => hilbert(self.tile_size, index),
+                };
+
+                let start = tile * self.tile_size;
+                match self.order {
+                    _ if self.width >= self.height => (start + x, y),
+                    // Turned on its side the Hilbert curve ends each tile next to the one above
+                    TraversalOrder::Hilbert => (y, start + x),
+                    _ => (x, start + y),
+                }
+            }
+        }
+    }
+}
+
+/// Get the offset of step `index` along the Z-order curve by splitting the bits between the axes
+fn z_order(index: u64) -> (u64, u64) {
+    let compact = |mut bits: u64| {
+        bits &= 0x5555_5555_5555_5555;
+        bits = (bits | (bits >> 1)) & 0x3333_3333_3333_3333;
+        bits = (bits | (bits >> 2)) & 0x0f0f_0f0f_0f0f_0f0f;
+        bits = (bits | (bits >> 4)) & 0x00ff_00ff_00ff_00ff;
+        bits = (bits | (bits >> 8)) & 0x0000_ffff_0000_ffff;
+        (bits | (bits >> 16)) & 0x0000_0000_ffff_ffff
+    };
+    (compact(index), compact(index >> 1))
+}
+
+/// Get the offset of step `index` along the Hilbert curve filling a square of `size` cells
+fn hilbert(size: u64, index: u64) -> (u64, u64) {
+    let (mut x, mut y) = (0, 0);
+    let mut index = index;
+    let mut side = 1;
+    while side < size {
+        let rx = 1 & (index / 2);
+        let ry = 1 & (index ^ rx);
+
+        // Turn the quadrant so the curve lines up with the next one
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+
+        x += side * rx;
+        y += side * ry;
+        index /= 4;
+        side *= 2;
+    }
+    (x, y)
+}
+
+impl Iterator for RectangleOrderIter {
+    type Item = Coord;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.end {
+            let (x, y) = self.offset(self.index);
+            self.index += 1;
+
+            // Curves may leave the rectangle
+            if x < self.width && y < self.height {
+                self.remaining -= 1;
+                return Some(Coord::new(
+                    self.position.x + x as i32,
+                    self.position.y + y as i32,
+                ));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Only the largest rectangles on 32 bit targets have more cells than fit in a usize
+        match usize::try_from(self.remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl ExactSizeIterator for RectangleOrderIter {}
@@ -0,0 +1,29 @@
+use coord_2d::Coord;
+
+use crate::shapes::{iters::RectangleIter, Rectangle, Shape};
+
+/// Iterates over the cells of a rectangle which are part of a shape, row by row
+///
+/// Each cell of the rectangle is checked once, so every cell is unique.
+#[derive(Debug, Clone)]
+pub struct FilteredIter<S: Shape> {
+    shape: S,
+    bounds: RectangleIter,
+}
+
+impl<S: Shape> FilteredIter<S> {
+    pub fn new(shape: S, bounds: Rectangle) -> Self {
+        Self {
+            shape,
+            bounds: bounds.into_iter(),
+        }
+    }
+}
+
+impl<S: Shape> Iterator for FilteredIter<S> {
+    type Item = Coord;
+    fn next(&mut self) -> Option<Self::Item> {
+        let shape = self.shape;
+        self.bounds.find(|&coord| shape.contains(coord))
+    }
+}
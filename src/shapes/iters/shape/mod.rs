@@ -0,0 +1,5 @@
+mod filtered_iter;
+pub use self::filtered_iter::*;
+
+mod spans_iter;
+pub use self::spans_iter::*;
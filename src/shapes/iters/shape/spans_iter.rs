@@ -0,0 +1,63 @@
+use coord_2d::Coord;
+
+use crate::shapes::{Rectangle, Shape};
+
+/// Iterates over the cells of a shape inside a rectangle, row by row
+///
+/// Rows are walked a span at a time using [`Shape::spans_within`], so shapes which clip their
+/// spans analytically never visit the cells outside of the rectangle.
+#[derive(Debug, Clone)]
+pub struct SpansIter<S: Shape> {
+    shape: S,
+    bounds: Rectangle,
+    y: i64,
+    x: i64,
+    // Last column of the span being walked
+    span_end: i64,
+}
+
+impl<S: Shape> SpansIter<S> {
+    pub fn new(shape: S, bounds: Rectangle) -> Self {
+        let left = bounds.left() as i64;
+        Self {
+            shape,
+            bounds,
+            y: bounds.bottom() as i64,
+            x: left,
+            span_end: left - 1,
+        }
+    }
+}
+
+impl<S: Shape> Iterator for SpansIter<S> {
+    type Item = Coord;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (left, right) = (self.bounds.left() as i64, self.bounds.right() as i64);
+        loop {
+            if self.x <= self.span_end {
+                let coord = Coord::new(self.x as i32, self.y as i32);
+                self.x += 1;
+                return Some(coord);
+            }
+            if self.y > self.bounds.top() as i64 {
+                return None;
+            }
+            if self.x > right {
+                self.y += 1;
+                self.x = left;
+                self.span_end = left - 1;
+                continue;
+            }
+
+            // The next span in the rest of the row
+            let rest = Rectangle::new(self.x as i32, self.y as i32, right as i32, self.y as i32);
+            match self.shape.spans_within(rest).next() {
+                Some((_, span)) => {
+                    self.x = *span.start() as i64;
+                    self.span_end = *span.end() as i64;
+                }
+                None => self.x = right + 1,
+            }
+        }
+    }
+}
@@ -1,24 +1,79 @@
-use std::fmt::{Debug, Display};
+use core::{
+    fmt::{Debug, Display},
+    iter::StepBy,
+    ops::RangeInclusive,
+};
+
+#[cfg(all(feature = "rand", feature = "alloc"))]
+use alloc::vec::Vec;
 
 use coord_2d::Coord;
+#[cfg(feature = "rand")]
+use rand::Rng;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::{
     iters::{
-        LineBresenhamIter, LineTunnelHorizontalVerticalIter, LineTunnelVerticalHorizontalIter,
+        LineBresenhamIter, LineSupercoverIter, LineTunnelHorizontalVerticalIter,
+        LineTunnelVerticalHorizontalIter, Octant,
     },
-    Rectangle, Shape,
+    DistanceMetric, Rectangle, Shape,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// Represents a Line on a grid
 pub struct Line {
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
     pub start: Coord,
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
     pub end: Coord,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Which cells are visited walking along a line
+pub enum LineKind {
+    /// The cells drawn by `LineBresenhamIter`, one per column or row
+    #[default]
+    Bresenham,
+    /// Every cell the line touches, drawn by `LineSupercoverIter`
+    Supercover,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Whether a cast checks the cell it starts from
+pub enum CastStart {
+    /// The starting cell can block the cast
+    Include,
+    /// The starting cell is always free, like the cell of whoever is casting
+    #[default]
+    Exclude,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// The outcome of casting along a line, `steps` saturates at `u32::MAX`
+pub enum CastResult {
+    /// No cell was blocking, `steps` cells were walked to reach `end`
+    Reached {
+        #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
+        end: Coord,
+        steps: u32,
+    },
+    /// `coord` was blocking, `steps` cells along the line
+    Hit {
+        #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
+        coord: Coord,
+        steps: u32,
+        /// The cell before `coord`, `None` if the starting cell was blocking
+        #[cfg_attr(feature = "serde", serde(with = "super::serialize::option_coord"))]
+        last_free: Option<Coord>,
+    },
+}
+
 // Constructors
 impl Line {
     /// Creates a new Line
@@ -30,10 +85,12 @@ impl Line {
 // Implementation
 impl Line {
     /// Get the total length of the line
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(self) -> u32 {
-        (self.end.x - self.start.x)
-            .abs()
-            .max((self.end.y - self.start.y).abs()) as u32
+        self.end
+            .x
+            .abs_diff(self.start.x)
+            .max(self.end.y.abs_diff(self.start.y))
             + 1
     }
 
@@ -48,8 +105,230 @@ impl Line {
     }
 }
 
+// Bresenham
+impl Line {
+    /// Get the Coord `step` cells along the line drawn by `LineBresenhamIter`
+    pub(crate) fn step_coord(self, step: u32) -> Coord {
+        let bresenham = Bresenham::new(self);
+        let step = step as i64;
+        bresenham.octant.from_offset((
+            (bresenham.start.0 as i64 + step) as i32,
+            (bresenham.start.1 as i64 + bresenham.rise(step)) as i32,
+        ))
+    }
+
+    /// Returns the Coord of the line closest to `position`
+    fn closest_coord(self, position: Coord, metric: DistanceMetric) -> Coord {
+        let bresenham = Bresenham::new(self);
+        let offset = bresenham.octant.to_offset(position);
+        let (x, y) = (
+            offset.0 as f64 - bresenham.start.0 as f64,
+            offset.1 as f64 - bresenham.start.1 as f64,
+        );
+        let slope = match bresenham.delta_x {
+            0 => 0.0,
+            delta_x => bresenham.delta_y as f64 / delta_x as f64,
+        };
+
+        // Each cell of the line is less than a row below the line through its center, and the
+        // metrics measure the same however the octant turns the line
+        metric
+            .closest_along(
+                position,
+                0..=bresenham.delta_x,
+                |step| {
+                    let step = step as f64;
+                    ((x - step).abs(), ((y - step * slope).abs() - 1.0).max(0.0))
+                },
+                |step| Some(self.step_coord(step as u32)),
+            )
+            .unwrap_or(self.start)
+    }
+
+    /// Returns the first and last step of the line which are inside `rectangle`
+    pub(crate) fn steps_within(self, rectangle: Rectangle) -> Option<(u32, u32)> {
+        if rectangle.size().is_empty() {
+            return None;
+        }
+
+        let bresenham = Bresenham::new(self);
+
+        // The rectangle relative to the line's octant
+        let corner0 = bresenham.octant.to_offset(rectangle.min());
+        let corner1 = bresenham.octant.to_offset(rectangle.max());
+        let (start_x, start_y) = (bresenham.start.0 as i64, bresenham.start.1 as i64);
+        let min_x = corner0.0.min(corner1.0) as i64 - start_x;
+        let max_x = corner0.0.max(corner1.0) as i64 - start_x;
+        let min_y = corner0.1.min(corner1.1) as i64 - start_y;
+        let max_y = corner0.1.max(corner1.1) as i64 - start_y;
+
+        // Steps are found in i128, as rows times columns may not fit in an i64
+        let (delta_x, delta_y) = (bresenham.delta_x as i128, bresenham.delta_y as i128);
+        let mut first = min_x.max(0) as i128;
+        let mut last = max_x.min(bresenham.delta_x) as i128;
+        if max_y < 0 {
+            return None;
+        }
+        if bresenham.delta_y == 0 {
+            if min_y > 0 {
+                return None;
+            }
+        } else {
+            // First step rising to `min_y` and last step before rising past `max_y`
+            if min_y > 0 {
+                first = first.max(ceil_div(min_y as i128 * delta_x, delta_y));
+            }
+            last = last.min(((max_y as i128 + 1) * delta_x - 1) / delta_y);
+        }
+
+        if first > last {
+            None
+        } else {
+            Some((first as u32, last as u32))
+        }
+    }
+}
+
+/// A line transformed into `Octant(0)`, where each step moves one column and rises at most one row
+struct Bresenham {
+    octant: Octant,
+    start: (i32, i32),
+    delta_x: i64,
+    delta_y: i64,
+}
+
+impl Bresenham {
+    fn new(line: Line) -> Self {
+        let octant = Octant::new(line.start, line.end);
+        let start = octant.to_offset(line.start);
+        let end = octant.to_offset(line.end);
+        Self {
+            octant,
+            start,
+            delta_x: end.0 as i64 - start.0 as i64,
+            delta_y: end.1 as i64 - start.1 as i64,
+        }
+    }
+
+    /// Rows risen after `step` steps, Bresenham rises once the error reaches a whole cell
+    fn rise(&self, step: i64) -> i64 {
+        if self.delta_x == 0 {
+            0
+        } else {
+            // Multiplied in i128 as both may be over 2³¹
+            (step as i128 * self.delta_y as i128 / self.delta_x as i128) as i64
+        }
+    }
+}
+
+fn ceil_div(numerator: i128, denominator: i128) -> i128 {
+    (numerator + denominator - 1) / denominator
+}
+
+// Clipping
+impl Line {
+    /// Returns the part of the line inside `rectangle`
+    ///
+    /// The returned line runs between the first and last cells inside `rectangle`, but may not
+    /// draw exactly the same cells in between. Use [`Line::clipped_iter`] for those.
+    pub fn clip(self, rectangle: Rectangle) -> Option<Self> {
+        let (first, last) = self.steps_within(rectangle)?;
+        Some(Self::new(self.step_coord(first), self.step_coord(last)))
+    }
+
+    /// Provides an iterator over only the cells of the line inside `rectangle`
+    pub fn clipped_iter(self, rectangle: Rectangle) -> LineBresenhamIter {
+        let (first, last) = self.steps_within(rectangle).unwrap_or((1, 0));
+        LineBresenhamIter::new_between(self.start, self.end, first, last)
+    }
+}
+
+// Stepping
+impl Line {
+    /// Get the cell `index` cells along the line drawn by `LineBresenhamIter`
+    ///
+    /// Returns `None` if the line is shorter than that.
+    pub fn cell_at(self, index: u32) -> Option<Coord> {
+        (index < self.len()).then(|| self.step_coord(index))
+    }
+
+    /// Get the cell a fraction `t` of the way along the line
+    ///
+    /// `t` is clamped between `0.0` (the start) and `1.0` (the end).
+    pub fn cell_at_fraction(self, t: f32) -> Coord {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        // Rounded to the nearest cell
+        let index = (t * (self.len() - 1) as f32 + 0.5) as u32;
+        self.step_coord(index.min(self.len() - 1))
+    }
+
+    /// Provides an iterator over every `step`th cell of the line, starting with the first
+    ///
+    /// Cells in between are jumped over rather than walked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is 0.
+    pub fn stepped_iter(self, step: usize) -> StepBy<LineBresenhamIter> {
+        self.into_iter().step_by(step)
+    }
+}
+
+// Casting
+impl Line {
+    /// Walks the line drawn by `LineBresenhamIter` until a cell for which `is_blocked` returns
+    /// true, ignoring the starting cell
+    pub fn cast<F: FnMut(Coord) -> bool>(self, is_blocked: F) -> CastResult {
+        self.cast_with(LineKind::Bresenham, CastStart::Exclude, is_blocked)
+    }
+
+    /// Walks the cells of `kind` until a cell for which `is_blocked` returns true
+    pub fn cast_with<F: FnMut(Coord) -> bool>(
+        self,
+        kind: LineKind,
+        start: CastStart,
+        is_blocked: F,
+    ) -> CastResult {
+        match kind {
+            LineKind::Bresenham => cast(self.into_iter(), start, is_blocked),
+            LineKind::Supercover => cast(self.supercover_iter(), start, is_blocked),
+        }
+    }
+}
+
+fn cast<I, F>(cells: I, start: CastStart, mut is_blocked: F) -> CastResult
+where
+    I: Iterator<Item = Coord>,
+    F: FnMut(Coord) -> bool,
+{
+    let mut last_free = None;
+    let mut steps = 0;
+    for (step, coord) in cells.enumerate() {
+        // Supercover lines between far apart points can walk more cells than fit in a u32
+        steps = u32::try_from(step).unwrap_or(u32::MAX);
+        if (step > 0 || start == CastStart::Include) && is_blocked(coord) {
+            return CastResult::Hit {
+                coord,
+                steps,
+                last_free,
+            };
+        }
+        last_free = Some(coord);
+    }
+    CastResult::Reached {
+        // Lines always have at least one cell
+        end: last_free.unwrap(),
+        steps,
+    }
+}
+
 // Iterator
 impl Line {
+    /// Provides an iterator over every cell the line touches
+    pub fn supercover_iter(self) -> LineSupercoverIter {
+        LineSupercoverIter::new(self.start, self.end)
+    }
+
     /// Provides an iterator over a line horizontal and then vertical reaching the ending
     pub fn tunnel_horizontal_vertical_iter(self) -> LineTunnelHorizontalVerticalIter {
         LineTunnelHorizontalVerticalIter::new(self.start, self.end)
@@ -83,9 +362,66 @@ impl Shape for Line {
         }
     }
 
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, mut f: F) {
+        for coord in self.clipped_iter(bounds) {
+            f(coord);
+        }
+    }
+
+    fn spans_within(self, bounds: Rectangle) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        // Each row of the line is drawn in one go
+        let mut coords = self.clipped_iter(bounds).peekable();
+        core::iter::from_fn(move || {
+            let first = coords.next()?;
+            let (mut left, mut right) = (first.x, first.x);
+            while let Some(coord) = coords.next_if(|coord| coord.y == first.y) {
+                left = left.min(coord.x);
+                right = right.max(coord.x);
+            }
+            Some((first.y, left..=right))
+        })
+    }
+
     fn aabb(self) -> Rectangle {
         Rectangle::from_corners(self.start, self.end)
     }
+
+    fn cell_count(self) -> usize {
+        self.len() as usize
+    }
+
+    fn perimeter_iter(self) -> impl Iterator<Item = Coord> {
+        // Every cell of a line is on its edge
+        self.into_iter()
+    }
+
+    fn perimeter_len(self) -> usize {
+        self.len() as usize
+    }
+
+    fn closest_cell(self, position: Coord, metric: DistanceMetric) -> Option<Coord> {
+        Some(self.closest_coord(position, metric))
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        let bresenham = Bresenham::new(self);
+        let offset = bresenham.octant.to_offset(position);
+        let step = offset.0 as i64 - bresenham.start.0 as i64;
+        (0..=bresenham.delta_x).contains(&step)
+            && offset.1 as i64 - bresenham.start.1 as i64 == bresenham.rise(step)
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> Option<Coord> {
+        Some(self.step_coord(rng.gen_range(0..self.len())))
+    }
+
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_n<R: Rng + ?Sized>(self, rng: &mut R, amount: usize) -> Vec<Coord> {
+        super::sample::sample_indices(rng, self.len() as usize, amount, |i| {
+            self.step_coord(i as u32)
+        })
+    }
 }
 
 impl IntoIterator for Line {
@@ -106,7 +442,7 @@ impl Default for Line {
 }
 
 impl Debug for Line {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Line {{ start: ({}, {}), end: ({}, {}) }}",
@@ -116,7 +452,7 @@ impl Debug for Line {
 }
 
 impl Display for Line {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Line {{\n\tstart: ({}, {}),\n\tend: ({}, {}),\n}}",
@@ -128,7 +464,9 @@ impl Display for Line {
 #[cfg(test)]
 mod test {
     use super::*;
-    use coord_2d::Coord;
+    use crate::shapes::test_helpers::check_double_ended;
+    use coord_2d::{Coord, Size};
+    use std::collections::HashSet;
 
     fn start() -> Coord {
         Coord::new(0, 0)
@@ -179,4 +517,285 @@ mod test {
             vec![Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2)]
         );
     }
+
+    #[test]
+    fn test_size_hint() {
+        let line = Line::new(Coord::new(3, -1), Coord::new(-4, 2));
+        assert_eq!(line.cell_count(), line.into_iter().count());
+
+        let mut iter = line.into_iter();
+        for len in (0..=line.cell_count()).rev() {
+            assert_eq!(iter.len(), len);
+            iter.next();
+        }
+    }
+
+    #[test]
+    fn test_clip() {
+        let line = Line::new(Coord::new(-100, -30), Coord::new(200, 70));
+        let rectangle = Rectangle::new(0, 0, 9, 9);
+        let clipped = line.clip(rectangle).unwrap();
+        assert!(rectangle.contains(clipped.start()));
+        assert!(rectangle.contains(clipped.end()));
+        assert!(line.contains(clipped.start()));
+        assert!(line.contains(clipped.end()));
+
+        let line = Line::new(Coord::new(-100, -30), Coord::new(200, -10));
+        assert_eq!(line.clip(rectangle), None);
+        assert_eq!(line.clipped_iter(rectangle).count(), 0);
+
+        // Empty rectangles have nothing to clip to
+        let line = Line::new(Coord::new(0, 0), Coord::new(0, 5));
+        let empty = Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3));
+        assert_eq!(line.clip(empty), None);
+        assert_eq!(line.clipped_iter(empty).count(), 0);
+    }
+
+    #[test]
+    fn test_clip_extreme() {
+        // Far enough apart to overflow subtracting in i32
+        let line = Line::new(
+            Coord::new(-2_000_000_000, 1_900_000_000),
+            Coord::new(2_000_000_000, -1_900_000_000),
+        );
+        assert_eq!(line.len(), 4_000_000_001);
+        let rectangle = Rectangle::new(-3, -3, 3, 3);
+        let clipped = line.clip(rectangle).unwrap();
+        assert!(rectangle.contains(clipped.start()));
+        assert!(rectangle.contains(clipped.end()));
+        assert!(line.contains(clipped.start()));
+        assert!(line.contains(clipped.end()));
+        assert!(line
+            .clipped_iter(rectangle)
+            .all(|coord| rectangle.contains(coord) && line.contains(coord)));
+
+        // The corners of the rectangle are far from the start of the line
+        let rectangle = Rectangle::new(1_999_999_990, -1_900_000_000, 2_000_000_000, 0);
+        let clipped = line.clip(rectangle).unwrap();
+        assert_eq!(clipped.end(), line.end());
+        assert!(rectangle.contains(clipped.start()));
+        assert_eq!(line.clipped_iter(rectangle).count(), 11);
+    }
+
+    #[test]
+    fn test_clipped_iter() {
+        let range = Rectangle::new(-5, -5, 5, 5);
+        let rectangle = Rectangle::new(-2, -1, 3, 2);
+        for start in range {
+            for end in range {
+                let line = Line::new(start, end);
+                let expected: Vec<Coord> = line
+                    .into_iter()
+                    .filter(|&coord| rectangle.contains(coord))
+                    .collect();
+                let iter = line.clipped_iter(rectangle);
+                assert_eq!(iter.len(), expected.len());
+                assert_eq!(iter.collect::<Vec<Coord>>(), expected, "{:?}", line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let range = Rectangle::new(-4, -4, 4, 4);
+        for start in range {
+            for end in range {
+                let line = Line::new(start, end);
+                let points: HashSet<Coord> = line.into_iter().collect();
+                for coord in line.aabb().inflate(1, 1) {
+                    assert_eq!(
+                        line.contains(coord),
+                        points.contains(&coord),
+                        "{:?} {:?}",
+                        line,
+                        coord
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_double_ended() {
+        let range = Rectangle::new(-4, -4, 4, 4);
+        let rectangle = Rectangle::new(-2, -1, 3, 2);
+        for end in range {
+            for start in [Coord::new(0, 0), Coord::new(-1, 3)] {
+                let line = Line::new(start, end);
+                check_double_ended(line.into_iter());
+                check_double_ended(line.clipped_iter(rectangle));
+                check_double_ended(line.tunnel_horizontal_vertical_iter());
+                check_double_ended(line.tunnel_vertical_horizontal_iter());
+            }
+        }
+        check_double_ended(Line::new(Coord::new(-90, 31), Coord::new(150, -7)).into_iter());
+    }
+
+    #[test]
+    fn test_cell_at() {
+        let range = Rectangle::new(-4, -4, 4, 4);
+        for end in range {
+            let line = Line::new(Coord::new(1, -2), end);
+            let cells: Vec<Coord> = line.into_iter().collect();
+            for (index, &coord) in cells.iter().enumerate() {
+                assert_eq!(line.cell_at(index as u32), Some(coord));
+            }
+            assert_eq!(line.cell_at(line.len()), None);
+
+            assert_eq!(line.cell_at_fraction(0.0), line.start());
+            assert_eq!(line.cell_at_fraction(1.0), line.end());
+            assert_eq!(line.cell_at_fraction(-3.0), line.start());
+            assert_eq!(line.cell_at_fraction(f32::NAN), line.start());
+            assert_eq!(line.cell_at_fraction(7.0), line.end());
+        }
+
+        let line = Line::new(Coord::new(0, 0), Coord::new(10, 5));
+        assert_eq!(line.cell_at_fraction(0.5), Coord::new(5, 2));
+        assert_eq!(line.cell_at_fraction(0.32), Coord::new(3, 1));
+    }
+
+    #[test]
+    fn test_stepped_iter() {
+        let range = Rectangle::new(-6, -6, 6, 6);
+        for end in range {
+            let line = Line::new(Coord::new(0, 1), end);
+            let cells: Vec<Coord> = line.into_iter().collect();
+            for step in 1..6 {
+                let expected: Vec<Coord> = cells.iter().copied().step_by(step).collect();
+                assert_eq!(line.stepped_iter(step).collect::<Vec<_>>(), expected);
+                let mut reversed: Vec<Coord> = line.stepped_iter(step).rev().collect();
+                reversed.reverse();
+                assert_eq!(reversed, expected);
+            }
+
+            // Jumping from both ends
+            let mut iter = line.into_iter();
+            assert_eq!(iter.nth(2), cells.get(2).copied());
+            let back = cells.len().checked_sub(2).filter(|&index| index > 2);
+            assert_eq!(iter.nth_back(1), back.map(|index| cells[index]));
+        }
+
+        let mut iter = Line::new(Coord::new(-200, 13), Coord::new(300, -17))
+            .clipped_iter(Rectangle::new(0, -20, 50, 20));
+        let expected: Vec<Coord> = iter.clone().collect();
+        assert_eq!(iter.nth(7), Some(expected[7]));
+        assert_eq!(iter.next(), Some(expected[8]));
+        assert_eq!(iter.nth(expected.len()), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_supercover_iter() {
+        let cells = |start, end| Line::new(start, end).supercover_iter().collect::<Vec<_>>();
+        assert_eq!(cells(start(), start()), vec![start()]);
+        assert_eq!(
+            cells(Coord::new(0, 0), Coord::new(3, 0)),
+            (0..=3).map(|x| Coord::new(x, 0)).collect::<Vec<_>>()
+        );
+        // Both cells beside each corner
+        assert_eq!(
+            cells(Coord::new(0, 0), Coord::new(-2, 2)),
+            vec![
+                Coord::new(0, 0),
+                Coord::new(-1, 0),
+                Coord::new(0, 1),
+                Coord::new(-1, 1),
+                Coord::new(-2, 1),
+                Coord::new(-1, 2),
+                Coord::new(-2, 2),
+            ]
+        );
+        assert_eq!(
+            cells(Coord::new(0, 0), Coord::new(3, 1)),
+            vec![
+                Coord::new(0, 0),
+                Coord::new(1, 0),
+                Coord::new(2, 0),
+                Coord::new(1, 1),
+                Coord::new(2, 1),
+                Coord::new(3, 1),
+            ]
+        );
+
+        // Supercover lines are 4-connected
+        for end in Rectangle::new(-6, -6, 6, 6) {
+            let line = Line::new(Coord::new(1, -2), end);
+            let supercover = line.supercover_iter().collect::<Vec<_>>();
+            assert_eq!(supercover.last(), Some(&end));
+            // Every cell's square touches the segment
+            let delta = end - line.start;
+            assert!(supercover.iter().all(|&coord| {
+                let offset = coord - line.start;
+                let cross = offset.x * delta.y - offset.y * delta.x;
+                2 * cross.abs() <= delta.x.abs() + delta.y.abs()
+            }));
+            for (i, &coord) in supercover.iter().enumerate().skip(1) {
+                assert!(
+                    supercover[..i]
+                        .iter()
+                        .any(|&other| (coord - other).x.abs() + (coord - other).y.abs() == 1),
+                    "{:?} {:?}",
+                    line,
+                    coord
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cast() {
+        let line = Line::new(Coord::new(0, 0), Coord::new(4, 2));
+        let wall = |x| move |coord: Coord| coord.x == x;
+
+        assert_eq!(
+            line.cast(wall(9)),
+            CastResult::Reached {
+                end: line.end,
+                steps: 4
+            }
+        );
+        assert_eq!(
+            line.cast(wall(3)),
+            CastResult::Hit {
+                coord: Coord::new(3, 1),
+                steps: 3,
+                last_free: Some(Coord::new(2, 1)),
+            }
+        );
+        assert_eq!(
+            line.cast(wall(0)),
+            CastResult::Reached {
+                end: line.end,
+                steps: 4
+            }
+        );
+        assert_eq!(
+            line.cast_with(LineKind::Bresenham, CastStart::Include, wall(0)),
+            CastResult::Hit {
+                coord: line.start,
+                steps: 0,
+                last_free: None,
+            }
+        );
+
+        // Supercover casts can't slip through diagonal gaps
+        let line = Line::new(Coord::new(0, 0), Coord::new(2, 2));
+        let gap = |coord: Coord| coord == Coord::new(1, 0) || coord == Coord::new(0, 1);
+        assert!(matches!(line.cast(gap), CastResult::Reached { .. }));
+        assert_eq!(
+            line.cast_with(LineKind::Supercover, CastStart::Exclude, gap),
+            CastResult::Hit {
+                coord: Coord::new(1, 0),
+                steps: 1,
+                last_free: Some(line.start),
+            }
+        );
+        assert_eq!(
+            line.cast_with(LineKind::Supercover, CastStart::Include, |_| false),
+            CastResult::Reached {
+                end: line.end,
+                steps: 6
+            }
+        );
+    }
 }
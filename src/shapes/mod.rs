@@ -6,8 +6,32 @@ pub use self::shape::*;
 mod circle;
 pub use self::circle::*;
 
+mod clipped;
+pub use self::clipped::*;
+
+mod complement;
+pub use self::complement::*;
+
+mod distance;
+pub use self::distance::*;
+
+mod intersects;
+pub use self::intersects::*;
+
 mod line;
 pub use self::line::*;
 
+mod outline;
+pub use self::outline::*;
+
+#[cfg(feature = "rand")]
+mod sample;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(test)]
+pub(crate) mod test_helpers;
+
 mod rectangle;
 pub use self::rectangle::*;
@@ -0,0 +1,288 @@
+use core::fmt::{Debug, Display};
+
+use coord_2d::Coord;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{iters::FilteredIter, Rectangle, Shape};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Which neighbours of a cell are considered adjacent
+pub enum Connectivity {
+    /// Only the orthogonal neighbours
+    #[default]
+    Four,
+    /// The orthogonal and diagonal neighbours
+    Eight,
+}
+
+impl Connectivity {
+    const FOUR: [Coord; 4] = [
+        Coord::new(0, -1),
+        Coord::new(-1, 0),
+        Coord::new(1, 0),
+        Coord::new(0, 1),
+    ];
+
+    const EIGHT: [Coord; 8] = [
+        Coord::new(-1, -1),
+        Coord::new(0, -1),
+        Coord::new(1, -1),
+        Coord::new(-1, 0),
+        Coord::new(1, 0),
+        Coord::new(-1, 1),
+        Coord::new(0, 1),
+        Coord::new(1, 1),
+    ];
+
+    /// Get the offsets to each neighbour of a cell
+    pub fn offsets(self) -> &'static [Coord] {
+        match self {
+            Connectivity::Four => &Self::FOUR,
+            Connectivity::Eight => &Self::EIGHT,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Which side of the shape's edge an outline follows
+pub enum OutlineKind {
+    /// The cells of the shape which have a neighbour outside of it
+    #[default]
+    Inner,
+    /// The cells outside of the shape which have a neighbour inside of it
+    Outer,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// Represents the outline of another shape on a grid
+pub struct Outline<S: Shape> {
+    pub shape: S,
+    pub connectivity: Connectivity,
+    pub kind: OutlineKind,
+}
+
+// Constructors
+impl<S: Shape> Outline<S> {
+    /// Creates a new Outline around `shape`
+    pub fn new(shape: S, connectivity: Connectivity, kind: OutlineKind) -> Self {
+        Self {
+            shape,
+            connectivity,
+            kind,
+        }
+    }
+}
+
+// Implementation
+impl<S: Shape> Outline<S> {
+    /// Get the shape being outlined
+    pub fn shape(self) -> S {
+        self.shape
+    }
+
+    /// Get the connectivity used to find neighbours
+    pub fn connectivity(self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Get which side of the edge is outlined
+    pub fn kind(self) -> OutlineKind {
+        self.kind
+    }
+}
+
+// Shape
+impl<S: Shape> Shape for Outline<S> {
+    fn for_each<F: FnMut(Coord)>(self, mut f: F) {
+        for coord in self {
+            f(coord);
+        }
+    }
+
+    fn aabb(self) -> Rectangle {
+        let rectangle = self.shape.aabb();
+        match self.kind {
+            OutlineKind::Inner => rectangle,
+            OutlineKind::Outer => Rectangle::new(
+                rectangle.left() - 1,
+                rectangle.bottom() - 1,
+                rectangle.right() + 1,
+                rectangle.top() + 1,
+            ),
+        }
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        let offsets = self.connectivity.offsets();
+        match self.kind {
+            OutlineKind::Inner => {
+                self.shape.contains(position)
+                    && offsets
+                        .iter()
+                        .any(|&offset| !self.shape.contains(position + offset))
+            }
+            OutlineKind::Outer => {
+                !self.shape.contains(position)
+                    && offsets
+                        .iter()
+                        .any(|&offset| self.shape.contains(position + offset))
+            }
+        }
+    }
+}
+
+impl<S: Shape> IntoIterator for Outline<S> {
+    type IntoIter = FilteredIter<Self>;
+    type Item = Coord;
+    fn into_iter(self) -> Self::IntoIter {
+        FilteredIter::new(self, self.aabb())
+    }
+}
+
+impl<S: Shape> Debug for Outline<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Outline {{ shape: {:?}, connectivity: {:?}, kind: {:?} }}",
+            self.shape, self.connectivity, self.kind
+        )
+    }
+}
+
+impl<S: Shape> Display for Outline<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Outline {{\n\tshape: {:?},\n\tconnectivity: {:?},\n\tkind: {:?},\n}}",
+            self.shape, self.connectivity, self.kind
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Complement, Line};
+
+    fn rect() -> Rectangle {
+        Rectangle::new(0, 0, 3, 2)
+    }
+
+    #[test]
+    fn test_inner_matches_border() {
+        let mut outline: Vec<Coord> = rect()
+            .outline(Connectivity::Four, OutlineKind::Inner)
+            .into_iter()
+            .collect();
+        let mut border: Vec<Coord> = rect().border_iter().collect();
+        outline.sort();
+        border.sort();
+        assert_eq!(outline, border);
+    }
+
+    #[test]
+    fn test_outer_four() {
+        let points: Vec<Coord> = Rectangle::new(0, 0, 0, 0)
+            .outline(Connectivity::Four, OutlineKind::Outer)
+            .into_iter()
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                Coord::new(0, -1),
+                Coord::new(-1, 0),
+                Coord::new(1, 0),
+                Coord::new(0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outer_eight() {
+        let outline = rect().outline(Connectivity::Eight, OutlineKind::Outer);
+        let mut points: Vec<Coord> = outline.into_iter().collect();
+        let mut expected: Vec<Coord> = outline.aabb().border_iter().collect();
+        points.sort();
+        expected.sort();
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn test_connectivity() {
+        let circle = Circle::new(Coord::new(0, 0), 5);
+        let four = circle
+            .outline(Connectivity::Four, OutlineKind::Inner)
+            .into_iter()
+            .count();
+        let eight = circle
+            .outline(Connectivity::Eight, OutlineKind::Inner)
+            .into_iter()
+            .count();
+        assert!(four < eight);
+    }
+
+    #[test]
+    fn test_contains() {
+        let circle = Circle::new(Coord::new(1, 1), 4);
+        for kind in [OutlineKind::Inner, OutlineKind::Outer] {
+            let outline = circle.outline(Connectivity::Four, kind);
+            let points: Vec<Coord> = outline.into_iter().collect();
+            for coord in outline.aabb() {
+                assert_eq!(outline.contains(coord), points.contains(&coord));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unique() {
+        let circle = Circle::new(Coord::new(3, -2), 7);
+        for kind in [OutlineKind::Inner, OutlineKind::Outer] {
+            let points: Vec<Coord> = circle
+                .outline(Connectivity::Eight, kind)
+                .into_iter()
+                .collect();
+            let mut unique = points.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(points.len(), unique.len());
+        }
+    }
+
+    fn check_perimeter(shape: impl Shape) {
+        let mut perimeter: Vec<Coord> = shape.perimeter_iter().collect();
+        let mut expected: Vec<Coord> = shape
+            .outline(Connectivity::Four, OutlineKind::Inner)
+            .into_iter()
+            .collect();
+        perimeter.sort();
+        expected.sort();
+        assert_eq!(perimeter, expected, "{:?}", shape);
+        assert_eq!(shape.perimeter_len(), expected.len(), "{:?}", shape);
+    }
+
+    #[test]
+    fn test_perimeter() {
+        for width in 0..5 {
+            for height in 0..5 {
+                check_perimeter(Rectangle::from_size(
+                    Coord::new(-1, 2),
+                    coord_2d::Size::new(width, height),
+                ));
+            }
+        }
+        for radius in 0..40 {
+            check_perimeter(Circle::new(Coord::new(3, -2), radius));
+        }
+        for end in Rectangle::new(-3, -3, 3, 3) {
+            check_perimeter(Line::new(Coord::new(0, 0), end));
+        }
+        check_perimeter(Complement::new(
+            Circle::new(Coord::new(0, 0), 3),
+            Rectangle::new(-4, -4, 4, 4),
+        ));
+    }
+}
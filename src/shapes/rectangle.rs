@@ -1,21 +1,52 @@
-use std::fmt::{Debug, Display};
+use core::{
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
 
-use crate::shapes::iters::{RectangleBorderIter, RectangleIter};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::shapes::iters::{RectangleBorderIter, RectangleIter, RectangleOrderIter};
 use coord_2d::{Coord, Size};
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::Shape;
+use super::{DistanceMetric, Intersects, Shape};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// Represents a Rectangle on a grid
 pub struct Rectangle {
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::CoordDef"))]
     pub position: Coord,
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::size"))]
     pub size: Size,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+/// The order the cells of a rectangle are visited in
+pub enum TraversalOrder {
+    /// Row by row from the bottom left, like `RectangleIter`
+    #[default]
+    RowMajor,
+    /// Column by column from the bottom left
+    ColumnMajor,
+    /// Row by row from the top right, the reverse of `RowMajor`
+    Reversed,
+    /// Row by row from the bottom left, every other row running right to left
+    Serpentine,
+    /// Along the Z-order (Morton) curve
+    ZOrder,
+    /// Along the Hilbert curve
+    Hilbert,
+}
+
 // Constructors
 impl Rectangle {
     /// Creates a new Rectangle with a known size
@@ -33,11 +64,22 @@ impl Rectangle {
         let min = Coord::new(x0.min(x1), y0.min(y1));
         let max = Coord::new(x0.max(x1), y0.max(y1));
         let size = Size::new(
-            (max.x - min.x).abs() as u32 + 1,
-            (max.y - min.y).abs() as u32 + 1,
+            (max.x - min.x).unsigned_abs() + 1,
+            (max.y - min.y).unsigned_abs() + 1,
         );
         Self::from_size(min, size)
     }
+
+    /// Creates a new Rectangle of `size` centered on `center`
+    ///
+    /// For even sizes the extra cell is placed on the right / top.
+    pub fn from_center(center: Coord, size: Size) -> Self {
+        let position = Coord::new(
+            center.x - (size.width() as i32 - 1) / 2,
+            center.y - (size.height() as i32 - 1) / 2,
+        );
+        Self::from_size(position, size)
+    }
 }
 
 // Implementation
@@ -101,21 +143,359 @@ impl Rectangle {
     }
 
     /// Determine if one rectangle intersects another
+    ///
+    /// Empty rectangles have no cells, so they never intersect anything.
     pub fn intersects(self, other: Self) -> bool {
-        self.left() <= other.right()
-            && self.right() >= other.left()
-            && self.bottom() <= other.top()
-            && self.top() >= other.bottom()
+        <Self as Intersects<Rectangle>>::intersects(self, other)
+    }
+
+    /// Determine if `other` is completely inside the rectangle
+    pub fn contains_rect(self, other: Self) -> bool {
+        other.left() >= self.left()
+            && other.right() <= self.right()
+            && other.bottom() >= self.bottom()
+            && other.top() <= self.top()
+    }
+
+    /// Returns the rectangle covered by both rectangles
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Self::new(
+            self.left().max(other.left()),
+            self.bottom().max(other.bottom()),
+            self.right().min(other.right()),
+            self.top().min(other.top()),
+        ))
+    }
+
+    /// Returns the smallest rectangle containing both rectangles
+    pub fn union(self, other: Self) -> Self {
+        Self::from_corners(
+            self.min().pairwise_min(other.min()),
+            self.max().pairwise_max(other.max()),
+        )
+    }
+
+    /// Grows the rectangle by `dx` columns on the left and right, and `dy` rows on the top and bottom
+    ///
+    /// Edges stop growing at the limits of `i32`, and sizes at the largest `Size` allowed.
+    pub fn inflate(self, dx: u32, dy: u32) -> Self {
+        // Grown in i64 so large amounts saturate rather than wrap
+        let grow = |start: i32, length: u32, amount: u32| {
+            let max_length = i32::MAX as i64 + 1;
+            let end = (start as i64 + length as i64 + amount as i64).min(i32::MAX as i64 + 1);
+            let start = (start as i64 - amount as i64).max(i32::MIN as i64);
+            (start as i32, (end - start).min(max_length) as u32)
+        };
+        let (left, width) = grow(self.left(), self.width(), dx);
+        let (bottom, height) = grow(self.bottom(), self.height(), dy);
+        Self::from_size(Coord::new(left, bottom), Size::new(width, height))
+    }
+
+    /// Shrinks the rectangle by `dx` columns on the left and right, and `dy` rows on the top and bottom
+    ///
+    /// Returns `None` if nothing would be left of the rectangle.
+    pub fn deflate(self, dx: u32, dy: u32) -> Option<Self> {
+        if self.width() as u64 <= dx as u64 * 2 || self.height() as u64 <= dy as u64 * 2 {
+            return None;
+        }
+
+        Some(Self::from_size(
+            Coord::new(self.left() + dx as i32, self.bottom() + dy as i32),
+            Size::new(self.width() - dx * 2, self.height() - dy * 2),
+        ))
+    }
+
+    /// Returns the closest Coord inside the rectangle to `coord`
+    ///
+    /// Returns `None` if the rectangle is empty.
+    pub fn clamp_coord(self, coord: Coord) -> Option<Coord> {
+        if self.size.is_empty() {
+            return None;
+        }
+
+        Some(Coord::new(
+            coord.x.clamp(self.left(), self.right()),
+            coord.y.clamp(self.bottom(), self.top()),
+        ))
+    }
+
+    /// Moves `other` the least distance needed to fit inside the rectangle
+    ///
+    /// If `other` is wider or taller than the rectangle it is also shrunk to fit.
+    pub fn clamp_rect(self, other: Self) -> Self {
+        let width = other.width().min(self.width());
+        let height = other.height().min(self.height());
+        let position = Coord::new(
+            other
+                .left()
+                .clamp(self.left(), self.right() - width as i32 + 1),
+            other
+                .bottom()
+                .clamp(self.bottom(), self.top() - height as i32 + 1),
+        );
+        Self::from_size(position, Size::new(width, height))
+    }
+}
+
+// Subtraction
+#[cfg(feature = "alloc")]
+impl Rectangle {
+    /// Returns up to four disjoint rectangles covering the cells of `self` which are not in `other`
+    pub fn subtract(self, other: Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![self];
+        };
+
+        let (left, right) = (overlap.left(), overlap.right());
+        let (bottom, top) = (overlap.bottom(), overlap.top());
+
+        let mut rectangles = Vec::with_capacity(4);
+        // Full width below the overlap
+        if bottom > self.bottom() {
+            rectangles.push(Self::new(
+                self.left(),
+                self.bottom(),
+                self.right(),
+                bottom - 1,
+            ));
+        }
+        // Full width above the overlap
+        if top < self.top() {
+            rectangles.push(Self::new(self.left(), top + 1, self.right(), self.top()));
+        }
+        // Left of the overlap
+        if left > self.left() {
+            rectangles.push(Self::new(self.left(), bottom, left - 1, top));
+        }
+        // Right of the overlap
+        if right < self.right() {
+            rectangles.push(Self::new(right + 1, bottom, self.right(), top));
+        }
+        rectangles
+    }
+
+    /// Returns disjoint rectangles covering the cells of `self` which are not in any of `others`
+    ///
+    /// Neighbouring pieces sharing a full edge are merged back together, but the result is not
+    /// guaranteed to be the smallest possible cover.
+    pub fn subtract_all(self, others: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut rectangles = vec![self];
+        for other in others {
+            rectangles = rectangles
+                .into_iter()
+                .flat_map(|rectangle| rectangle.subtract(other))
+                .collect();
+        }
+
+        // Merge any pieces which line up
+        let mut merged = true;
+        while merged {
+            merged = false;
+            'search: for i in 0..rectangles.len() {
+                for j in (i + 1)..rectangles.len() {
+                    if let Some(rectangle) = rectangles[i].try_merge(rectangles[j]) {
+                        rectangles[i] = rectangle;
+                        rectangles.swap_remove(j);
+                        merged = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        rectangles
+    }
+
+    /// Combines two rectangles sharing a full edge into one
+    fn try_merge(self, other: Self) -> Option<Self> {
+        let columns = self.left() == other.left() && self.right() == other.right();
+        let rows = self.bottom() == other.bottom() && self.top() == other.top();
+        if (columns && (self.top() + 1 == other.bottom() || other.top() + 1 == self.bottom()))
+            || (rows && (self.right() + 1 == other.left() || other.right() + 1 == self.left()))
+        {
+            Some(self.union(other))
+        } else {
+            None
+        }
+    }
+}
+
+// Splitting
+impl Rectangle {
+    /// Splits the rectangle into a bottom and top piece, `at` being the first row of the top piece
+    pub fn split_horizontal(self, at: i32) -> Option<(Self, Self)> {
+        self.split_horizontal_with_gap(at, 0)
+    }
+
+    /// Splits the rectangle into a bottom and top piece leaving `gap` rows between them, `at` being
+    /// the first row of the gap
+    pub fn split_horizontal_with_gap(self, at: i32, gap: u32) -> Option<(Self, Self)> {
+        let top_start = at + gap as i32;
+        if at <= self.bottom() || top_start > self.top() {
+            return None;
+        }
+
+        Some((
+            Self::new(self.left(), self.bottom(), self.right(), at - 1),
+            Self::new(self.left(), top_start, self.right(), self.top()),
+        ))
+    }
+
+    /// Splits the rectangle into a left and right piece, `at` being the first column of the right piece
+    pub fn split_vertical(self, at: i32) -> Option<(Self, Self)> {
+        self.split_vertical_with_gap(at, 0)
+    }
+
+    /// Splits the rectangle into a left and right piece leaving `gap` columns between them, `at`
+    /// being the first column of the gap
+    pub fn split_vertical_with_gap(self, at: i32, gap: u32) -> Option<(Self, Self)> {
+        let right_start = at + gap as i32;
+        if at <= self.left() || right_start > self.right() {
+            return None;
+        }
+
+        Some((
+            Self::new(self.left(), self.bottom(), at - 1, self.top()),
+            Self::new(right_start, self.bottom(), self.right(), self.top()),
+        ))
+    }
+
+    /// Splits the longest side of the rectangle so the first piece takes `ratio` of it
+    pub fn split_ratio(self, ratio: f32) -> Option<(Self, Self)> {
+        self.split_ratio_with_gap(ratio, 0)
+    }
+
+    /// Splits the longest side of the rectangle so the first piece takes `ratio` of the space left
+    /// after the gap
+    ///
+    /// Both pieces are always at least one cell wide.
+    pub fn split_ratio_with_gap(self, ratio: f32, gap: u32) -> Option<(Self, Self)> {
+        let length = self.width().max(self.height());
+        let available = length.checked_sub(gap)?;
+        if available < 2 {
+            return None;
+        }
+
+        // Rounded to the nearest cell
+        let first =
+            ((available as f32 * ratio.clamp(0.0, 1.0) + 0.5) as u32).clamp(1, available - 1);
+        if self.width() >= self.height() {
+            self.split_vertical_with_gap(self.left() + first as i32, gap)
+        } else {
+            self.split_horizontal_with_gap(self.bottom() + first as i32, gap)
+        }
+    }
+
+    /// Splits the rectangle into a grid of `columns` by `rows` rectangles
+    ///
+    /// The pieces are returned row by row starting from the bottom left, any leftover cells are
+    /// given to the first columns and rows. Returns an empty Vec if the rectangle is too small.
+    #[cfg(feature = "alloc")]
+    pub fn subdivide(self, columns: u32, rows: u32) -> Vec<Self> {
+        self.subdivide_with_gap(columns, rows, 0)
+    }
+
+    /// Splits the rectangle into a grid of `columns` by `rows` rectangles with `gap` cells between
+    /// each of them
+    ///
+    /// The pieces are returned row by row starting from the bottom left, any leftover cells are
+    /// given to the first columns and rows. Returns an empty Vec if the rectangle is too small.
+    #[cfg(feature = "alloc")]
+    pub fn subdivide_with_gap(self, columns: u32, rows: u32, gap: u32) -> Vec<Self> {
+        let (Some(xs), Some(ys)) = (
+            Self::partition(self.left(), self.width(), columns, gap),
+            Self::partition(self.bottom(), self.height(), rows, gap),
+        ) else {
+            return Vec::new();
+        };
+
+        let mut rectangles = Vec::with_capacity(xs.len() * ys.len());
+        for &(bottom, top) in &ys {
+            for &(left, right) in &xs {
+                rectangles.push(Self::new(left, bottom, right, top));
+            }
+        }
+        rectangles
+    }
+
+    /// Divides `length` cells from `start` into `count` inclusive ranges separated by `gap` cells
+    #[cfg(feature = "alloc")]
+    fn partition(start: i32, length: u32, count: u32, gap: u32) -> Option<Vec<(i32, i32)>> {
+        if count == 0 {
+            return None;
+        }
+
+        let available = length.checked_sub(gap.checked_mul(count - 1)?)?;
+        if available < count {
+            return None;
+        }
+
+        let base = available / count;
+        let remainder = available % count;
+        let mut ranges = Vec::with_capacity(count as usize);
+        let mut position = start;
+        for i in 0..count {
+            let size = base + u32::from(i < remainder);
+            ranges.push((position, position + size as i32 - 1));
+            position += (size + gap) as i32;
+        }
+        Some(ranges)
     }
 }
 
 // Iterators
 impl Rectangle {
+    /// Provides an iterator over the cells of the rectangle in the given order
+    ///
+    /// The curves are drawn over square tiles laid along the longest side of the rectangle, with
+    /// each tile as wide as the shortest side rounded up to a power of two. When the shortest
+    /// side is a power of two, each step of the Hilbert curve moves to a neighbouring cell, also
+    /// from one tile to the next.
+    pub fn iter_ordered(self, order: TraversalOrder) -> RectangleOrderIter {
+        RectangleOrderIter::new(self.position, self.size, order)
+    }
+
     /// Provides an iterator over the outer most border of the rectangle
     pub fn border_iter(self) -> RectangleBorderIter {
         RectangleBorderIter::new(self.position, self.size)
     }
 
+    /// Returns the cell of the border closest to `position`
+    ///
+    /// Ties are broken as described by [`DistanceMetric::is_closer`]. Returns `None` if the
+    /// rectangle is empty.
+    pub fn closest_border_cell(self, position: Coord, metric: DistanceMetric) -> Option<Coord> {
+        let clamped = self.clamp_coord(position)?;
+        if clamped != position
+            || self.width() <= 2
+            || self.height() <= 2
+            || position.x == self.left()
+            || position.x == self.right()
+            || position.y == self.bottom()
+            || position.y == self.top()
+        {
+            // Already on the border
+            return Some(clamped);
+        }
+
+        // Straight out to each side
+        metric
+            .closest(
+                position,
+                [
+                    Coord::new(position.x, self.bottom()),
+                    Coord::new(self.left(), position.y),
+                    Coord::new(self.right(), position.y),
+                    Coord::new(position.x, self.top()),
+                ],
+            )
+            .or(Some(clamped))
+    }
+
     /// Calls `f` for each Coord in the border
     pub fn for_each_border<F: FnMut(Coord)>(self, mut f: F) {
         for coord in self.border_iter() {
@@ -124,6 +504,17 @@ impl Rectangle {
     }
 }
 
+// Parallel
+#[cfg(feature = "rayon")]
+impl Rectangle {
+    /// Provides a parallel iterator over the cells of the rectangle, split by rows
+    pub fn par_iter(self) -> impl ParallelIterator<Item = Coord> {
+        (self.bottom()..=self.top())
+            .into_par_iter()
+            .flat_map_iter(move |y| (self.left()..=self.right()).map(move |x| Coord::new(x, y)))
+    }
+}
+
 // Shape
 impl Shape for Rectangle {
     fn for_each<F: FnMut(Coord)>(self, mut f: F) {
@@ -132,9 +523,76 @@ impl Shape for Rectangle {
         }
     }
 
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, mut f: F) {
+        if let Some(overlap) = self.intersection(bounds) {
+            for coord in overlap {
+                f(coord);
+            }
+        }
+    }
+
+    fn spans_within(self, bounds: Rectangle) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        self.intersection(bounds).into_iter().flat_map(|overlap| {
+            (overlap.bottom()..=overlap.top()).map(move |y| (y, overlap.left()..=overlap.right()))
+        })
+    }
+
     fn aabb(self) -> Rectangle {
         self
     }
+
+    fn cell_count(self) -> usize {
+        // Multiplied as usize so large rectangles don't overflow
+        self.width() as usize * self.height() as usize
+    }
+
+    fn perimeter_iter(self) -> impl Iterator<Item = Coord> {
+        self.border_iter()
+    }
+
+    fn perimeter_len(self) -> usize {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        if width <= 2 || height <= 2 {
+            // No cells in the middle
+            width * height
+        } else {
+            2 * (width + height) - 4
+        }
+    }
+
+    fn closest_cell(self, position: Coord, _metric: DistanceMetric) -> Option<Coord> {
+        // Closest along both axes at once
+        self.clamp_coord(position)
+    }
+
+    #[cfg(feature = "rand")]
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> Option<Coord> {
+        if self.size.is_empty() {
+            return None;
+        }
+        Some(Coord::new(
+            rng.gen_range(self.left()..=self.right()),
+            rng.gen_range(self.bottom()..=self.top()),
+        ))
+    }
+
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_n<R: Rng + ?Sized>(self, rng: &mut R, amount: usize) -> Vec<Coord> {
+        let width = self.size.width() as usize;
+        super::sample::sample_indices(rng, self.cell_count(), amount, |i| {
+            Coord::new(
+                self.left() + (i % width) as i32,
+                self.bottom() + (i / width) as i32,
+            )
+        })
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        position.x >= self.left()
+            && position.x <= self.right()
+            && position.y >= self.bottom()
+            && position.y <= self.top()
+    }
 }
 
 impl IntoIterator for Rectangle {
@@ -155,7 +613,7 @@ impl Default for Rectangle {
 }
 
 impl Debug for Rectangle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Rectangle {{ position: ({}, {}), size: ({}, {}) }}",
@@ -168,7 +626,7 @@ impl Debug for Rectangle {
 }
 
 impl Display for Rectangle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Rectangle {{\n\tposition: ({}, {}),\n\tsize: ({}, {})\n}}",
@@ -183,6 +641,7 @@ impl Display for Rectangle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shapes::test_helpers::check_double_ended;
     use coord_2d::{Coord, Size};
 
     fn size() -> Size {
@@ -300,6 +759,280 @@ mod tests {
         let rect1 = Rectangle::new(0, 0, 10, 10);
         let rect2 = Rectangle::new(11, 11, 20, 20);
         assert!(!rect1.intersects(rect2));
+
+        let empty = Rectangle::from_size(Coord::new(2, 2), Size::new(0, 3));
+        assert!(!rect1.intersects(empty));
+        assert!(!empty.intersects(rect1));
+    }
+
+    #[test]
+    fn test_from_center() {
+        let rect = Rectangle::from_center(Coord::new(5, 5), Size::new(3, 4));
+        assert_eq!(rect, Rectangle::new(4, 4, 6, 7));
+        assert_eq!(rect.center(), Coord::new(5, 5));
+    }
+
+    #[test]
+    fn test_contains() {
+        let rect = rect_from_size();
+        assert!(rect.contains(min()));
+        assert!(rect.contains(max()));
+        assert!(!rect.contains(Coord::new(min().x - 1, min().y)));
+        assert!(!rect.contains(Coord::new(max().x, max().y + 1)));
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        let rect = Rectangle::new(0, 0, 10, 10);
+        assert!(rect.contains_rect(rect));
+        assert!(rect.contains_rect(Rectangle::new(2, 3, 4, 10)));
+        assert!(!rect.contains_rect(Rectangle::new(2, 3, 4, 11)));
+        assert!(!rect.contains_rect(Rectangle::new(-1, 3, 4, 5)));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let rect1 = Rectangle::new(0, 0, 10, 10);
+        let rect2 = Rectangle::new(5, -5, 15, 5);
+        assert_eq!(rect1.intersection(rect2), Some(Rectangle::new(5, 0, 10, 5)));
+
+        let rect2 = Rectangle::new(10, 10, 20, 20);
+        assert_eq!(
+            rect1.intersection(rect2),
+            Some(Rectangle::new(10, 10, 10, 10))
+        );
+
+        let rect2 = Rectangle::new(11, 0, 20, 20);
+        assert_eq!(rect1.intersection(rect2), None);
+    }
+
+    #[test]
+    fn test_union() {
+        let rect1 = Rectangle::new(0, 0, 2, 2);
+        let rect2 = Rectangle::new(5, -5, 6, 1);
+        assert_eq!(rect1.union(rect2), Rectangle::new(0, -5, 6, 2));
+    }
+
+    #[test]
+    fn test_inflate_deflate() {
+        let rect = Rectangle::new(0, 0, 4, 4);
+        assert_eq!(rect.inflate(1, 2), Rectangle::new(-1, -2, 5, 6));
+        assert_eq!(rect.deflate(1, 2), Some(Rectangle::new(1, 2, 3, 2)));
+        assert_eq!(rect.deflate(2, 3), None);
+        assert_eq!(rect.inflate(3, 3).deflate(3, 3), Some(rect));
+
+        // Amounts too large to fit in an i32
+        assert_eq!(rect.deflate(u32::MAX / 2 + 1, 0), None);
+        assert_eq!(rect.deflate(0, u32::MAX), None);
+        let inflated = rect.inflate(u32::MAX, 1);
+        assert_eq!(inflated.left(), i32::MIN);
+        assert_eq!(inflated.width(), i32::MAX as u32 + 1);
+        assert_eq!(inflated.bottom(), -1);
+        assert_eq!(inflated.height(), 7);
+    }
+
+    #[test]
+    fn test_clamp_coord() {
+        let rect = Rectangle::new(0, 0, 4, 4);
+        assert_eq!(rect.clamp_coord(Coord::new(2, 3)), Some(Coord::new(2, 3)));
+        assert_eq!(rect.clamp_coord(Coord::new(-2, 9)), Some(Coord::new(0, 4)));
+
+        let empty = Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3));
+        assert_eq!(empty.clamp_coord(Coord::new(2, 3)), None);
+    }
+
+    #[test]
+    fn test_clamp_rect() {
+        let rect = Rectangle::new(0, 0, 9, 9);
+        let inside = Rectangle::new(2, 2, 4, 4);
+        assert_eq!(rect.clamp_rect(inside), inside);
+        assert_eq!(
+            rect.clamp_rect(Rectangle::new(8, -3, 11, -1)),
+            Rectangle::new(6, 0, 9, 2)
+        );
+        assert_eq!(
+            rect.clamp_rect(Rectangle::new(-5, 3, 20, 4)),
+            Rectangle::new(0, 3, 9, 4)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    fn assert_cover(rectangles: &[Rectangle], expected: impl Iterator<Item = Coord>) {
+        let mut points: Vec<Coord> = rectangles.iter().flat_map(|&r| r.into_iter()).collect();
+        let mut expected: Vec<Coord> = expected.collect();
+        points.sort();
+        expected.sort();
+        assert_eq!(points, expected);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_subtract() {
+        let rect = Rectangle::new(0, 0, 9, 9);
+
+        let other = Rectangle::new(20, 20, 30, 30);
+        assert_eq!(rect.subtract(other), vec![rect]);
+
+        let other = Rectangle::new(-1, -1, 10, 10);
+        assert!(rect.subtract(other).is_empty());
+
+        let other = Rectangle::new(3, 4, 5, 6);
+        let pieces = rect.subtract(other);
+        assert_eq!(pieces.len(), 4);
+        assert_cover(&pieces, rect.into_iter().filter(|&c| !other.contains(c)));
+
+        let other = Rectangle::new(-5, 8, 4, 20);
+        let pieces = rect.subtract(other);
+        assert_eq!(pieces.len(), 2);
+        assert_cover(&pieces, rect.into_iter().filter(|&c| !other.contains(c)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_subtract_all() {
+        let rect = Rectangle::new(0, 0, 9, 9);
+        let others = [
+            Rectangle::new(2, 2, 3, 3),
+            Rectangle::new(5, -1, 6, 4),
+            Rectangle::new(1, 7, 12, 8),
+        ];
+        let pieces = rect.subtract_all(others);
+        assert_cover(
+            &pieces,
+            rect.into_iter()
+                .filter(|&c| !others.iter().any(|other| other.contains(c))),
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_subtract_all_merges() {
+        let rect = Rectangle::new(0, 0, 3, 1);
+        let pieces = rect.subtract_all([Rectangle::new(1, 0, 1, 0), Rectangle::new(1, 1, 1, 1)]);
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces.contains(&Rectangle::new(0, 0, 0, 1)));
+        assert!(pieces.contains(&Rectangle::new(2, 0, 3, 1)));
+    }
+
+    #[test]
+    fn test_split_horizontal() {
+        let rect = Rectangle::new(0, 0, 4, 9);
+        assert_eq!(
+            rect.split_horizontal(3),
+            Some((Rectangle::new(0, 0, 4, 2), Rectangle::new(0, 3, 4, 9)))
+        );
+        assert_eq!(
+            rect.split_horizontal_with_gap(3, 2),
+            Some((Rectangle::new(0, 0, 4, 2), Rectangle::new(0, 5, 4, 9)))
+        );
+        assert_eq!(rect.split_horizontal(0), None);
+        assert_eq!(rect.split_horizontal(10), None);
+        assert_eq!(rect.split_horizontal_with_gap(8, 2), None);
+    }
+
+    #[test]
+    fn test_split_vertical() {
+        let rect = Rectangle::new(-5, 0, 4, 4);
+        assert_eq!(
+            rect.split_vertical(-1),
+            Some((Rectangle::new(-5, 0, -2, 4), Rectangle::new(-1, 0, 4, 4)))
+        );
+        assert_eq!(
+            rect.split_vertical_with_gap(-1, 1),
+            Some((Rectangle::new(-5, 0, -2, 4), Rectangle::new(0, 0, 4, 4)))
+        );
+        assert_eq!(rect.split_vertical(-5), None);
+        assert_eq!(rect.split_vertical_with_gap(4, 1), None);
+    }
+
+    #[test]
+    fn test_split_ratio() {
+        let rect = Rectangle::new(0, 0, 9, 3);
+        let (left, right) = rect.split_ratio(0.3).unwrap();
+        assert_eq!(left, Rectangle::new(0, 0, 2, 3));
+        assert_eq!(right, Rectangle::new(3, 0, 9, 3));
+
+        let (bottom, top) = Rectangle::new(0, 0, 3, 9)
+            .split_ratio_with_gap(0.5, 2)
+            .unwrap();
+        assert_eq!(bottom, Rectangle::new(0, 0, 3, 3));
+        assert_eq!(top, Rectangle::new(0, 6, 3, 9));
+
+        let (left, right) = rect.split_ratio(0.0).unwrap();
+        assert_eq!(left.width(), 1);
+        assert_eq!(right.width(), 9);
+
+        assert_eq!(
+            Rectangle::new(0, 0, 2, 0).split_ratio_with_gap(0.5, 2),
+            None
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_subdivide() {
+        let rect = Rectangle::new(0, 0, 6, 3);
+        let pieces = rect.subdivide(3, 2);
+        assert_eq!(
+            pieces,
+            vec![
+                Rectangle::new(0, 0, 2, 1),
+                Rectangle::new(3, 0, 4, 1),
+                Rectangle::new(5, 0, 6, 1),
+                Rectangle::new(0, 2, 2, 3),
+                Rectangle::new(3, 2, 4, 3),
+                Rectangle::new(5, 2, 6, 3),
+            ]
+        );
+        assert_cover(&pieces, rect.into_iter());
+
+        let pieces = rect.subdivide_with_gap(2, 2, 1);
+        assert_eq!(
+            pieces,
+            vec![
+                Rectangle::new(0, 0, 2, 1),
+                Rectangle::new(4, 0, 6, 1),
+                Rectangle::new(0, 3, 2, 3),
+                Rectangle::new(4, 3, 6, 3),
+            ]
+        );
+
+        assert!(rect.subdivide(8, 1).is_empty());
+        assert!(rect.subdivide_with_gap(1, 2, 3).is_empty());
+        assert!(rect.subdivide(0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_cell_count() {
+        assert_eq!(rect_from_size().cell_count(), 50);
+        assert_eq!(rect_from_size().border_iter().len(), 26);
+        assert_eq!(
+            Rectangle::from_size(Coord::new(0, 0), Size::new(70001, 70001)).cell_count(),
+            70001 * 70001
+        );
+    }
+
+    #[test]
+    fn test_size_hint() {
+        for rect in [
+            Rectangle::new(0, 0, 0, 0),
+            Rectangle::new(0, 0, 0, 4),
+            Rectangle::new(0, 0, 4, 0),
+            Rectangle::new(-2, 3, 1, 4),
+            rect_from_size(),
+        ] {
+            let mut iter = rect.into_iter();
+            for len in (0..=rect.cell_count()).rev() {
+                assert_eq!(iter.size_hint(), (len, Some(len)));
+                iter.next();
+            }
+
+            let mut iter = rect.border_iter();
+            for len in (0..=rect.border_iter().count()).rev() {
+                assert_eq!(iter.size_hint(), (len, Some(len)));
+                iter.next();
+            }
+        }
     }
 
     #[test]
@@ -332,4 +1065,183 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_empty_iter() {
+        // Rectangles without rows or columns have no cells, not one
+        for size in [Size::new(0, 0), Size::new(0, 3), Size::new(3, 0)] {
+            let rect = Rectangle::from_size(Coord::new(2, -1), size);
+            assert_eq!(rect.into_iter().next(), None);
+            assert_eq!(rect.border_iter().next(), None);
+            assert_eq!(rect.into_iter().len(), 0);
+            assert_eq!(rect.border_iter().len(), 0);
+            assert_eq!(rect.perimeter_len(), 0);
+        }
+    }
+
+    #[test]
+    fn test_border_iter() {
+        // Borders away from the origin stay on the edge of the rectangle
+        let rect = Rectangle::new(3, -4, 6, 2);
+        let edge: Vec<Coord> = rect
+            .into_iter()
+            .filter(|coord| {
+                coord.x == rect.left()
+                    || coord.x == rect.right()
+                    || coord.y == rect.bottom()
+                    || coord.y == rect.top()
+            })
+            .collect();
+        assert_eq!(rect.border_iter().collect::<Vec<_>>(), edge);
+
+        // Single columns walk each cell once
+        let column = Rectangle::new(2, 1, 2, 5);
+        assert_eq!(
+            column.border_iter().collect::<Vec<_>>(),
+            column.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_iter_large() {
+        // More cells than fit in a u32
+        let rect = Rectangle::from_size(Coord::new(-5, 3), Size::new(70001, 70001));
+        let mut iter = rect.into_iter();
+        assert_eq!(iter.len(), 70001 * 70001);
+        assert_eq!(iter.next_back(), Some(rect.max()));
+        assert_eq!(iter.nth(70001), Some(Coord::new(-5, 4)));
+    }
+
+    #[test]
+    fn test_double_ended() {
+        for rect in [
+            Rectangle::new(0, 0, 0, 0),
+            Rectangle::new(0, 0, 0, 4),
+            Rectangle::new(0, 0, 4, 0),
+            Rectangle::new(0, 0, 1, 4),
+            Rectangle::new(-2, 3, 1, 4),
+            Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3)),
+            rect_from_size(),
+        ] {
+            check_double_ended(rect.into_iter());
+            check_double_ended(rect.border_iter());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_iter() {
+        use rayon::iter::ParallelIterator;
+
+        for rect in [
+            Rectangle::new(0, 0, 0, 0),
+            Rectangle::from_size(Coord::new(2, 1), Size::new(0, 3)),
+            rect_from_size(),
+            Rectangle::new(-40, -3, 50, 70),
+        ] {
+            let mut points: Vec<Coord> = rect.par_iter().collect();
+            let mut expected: Vec<Coord> = rect.into_iter().collect();
+            points.sort();
+            expected.sort();
+            assert_eq!(points, expected);
+        }
+    }
+
+    const ORDERS: [TraversalOrder; 6] = [
+        TraversalOrder::RowMajor,
+        TraversalOrder::ColumnMajor,
+        TraversalOrder::Reversed,
+        TraversalOrder::Serpentine,
+        TraversalOrder::ZOrder,
+        TraversalOrder::Hilbert,
+    ];
+
+    #[test]
+    fn test_iter_ordered_cells() {
+        for width in 0..10 {
+            for height in [0, 1, 2, 3, 7, 8, 9, 33] {
+                let rect = Rectangle::from_size(Coord::new(-3, 2), Size::new(width, height));
+                let mut expected: Vec<Coord> = rect.into_iter().collect();
+                expected.sort();
+                for order in ORDERS {
+                    let iter = rect.iter_ordered(order);
+                    assert_eq!(iter.len(), expected.len());
+                    let mut points: Vec<Coord> = iter.collect();
+                    points.sort();
+                    assert_eq!(points, expected, "{:?} {:?}", rect, order);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_iter_ordered() {
+        let rect = Rectangle::new(0, 0, 2, 1);
+        let points = |order| rect.iter_ordered(order).collect::<Vec<Coord>>();
+        let coords = |coords: &[(i32, i32)]| {
+            coords
+                .iter()
+                .map(|&(x, y)| Coord::new(x, y))
+                .collect::<Vec<Coord>>()
+        };
+
+        assert_eq!(
+            points(TraversalOrder::RowMajor),
+            rect.into_iter().collect::<Vec<Coord>>()
+        );
+        assert_eq!(
+            points(TraversalOrder::Reversed),
+            rect.into_iter().rev().collect::<Vec<Coord>>()
+        );
+        assert_eq!(
+            points(TraversalOrder::ColumnMajor),
+            coords(&[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)])
+        );
+        assert_eq!(
+            points(TraversalOrder::Serpentine),
+            coords(&[(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)])
+        );
+
+        let square = Rectangle::new(0, 0, 3, 3);
+        assert_eq!(
+            square
+                .iter_ordered(TraversalOrder::ZOrder)
+                .take(6)
+                .collect::<Vec<Coord>>(),
+            coords(&[(0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (3, 0)])
+        );
+    }
+
+    #[test]
+    fn test_iter_ordered_large() {
+        // More cells than fit in a u32
+        let rect = Rectangle::from_size(Coord::new(0, 0), Size::new(70001, 70001));
+        for order in [TraversalOrder::ColumnMajor, TraversalOrder::Hilbert] {
+            assert_eq!(rect.iter_ordered(order).len(), 70001 * 70001);
+        }
+    }
+
+    #[test]
+    fn test_iter_ordered_hilbert() {
+        // Each step of the curve moves to a neighbouring cell, also between tiles
+        for size in [
+            Size::new(2, 2),
+            Size::new(4, 4),
+            Size::new(16, 16),
+            Size::new(64, 64),
+            Size::new(12, 4),
+            Size::new(4, 12),
+            Size::new(1, 5),
+            Size::new(2, 32),
+        ] {
+            let rect = Rectangle::from_size(Coord::new(5, -9), size);
+            let points: Vec<Coord> = rect.iter_ordered(TraversalOrder::Hilbert).collect();
+            assert_eq!(points.len(), rect.cell_count());
+            assert_eq!(points[0], rect.min());
+            for pair in points.windows(2) {
+                let step = pair[1] - pair[0];
+                assert_eq!(step.x.abs() + step.y.abs(), 1, "{:?}", pair);
+            }
+        }
+    }
 }
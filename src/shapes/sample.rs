@@ -0,0 +1,307 @@
+#[cfg(feature = "std")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use coord_2d::Coord;
+use rand::Rng;
+
+use super::Shape;
+
+/// How many random cells of the bounding box are tried before counting the cells of the shape
+const REJECTION_ATTEMPTS: usize = 32;
+
+/// Returns a uniformly random cell of `shape` by picking cells of its bounding box until one is
+/// part of the shape
+///
+/// Falls back to picking by index so shapes which barely fill their bounding box (or are empty)
+/// still finish.
+pub(crate) fn rejection_sample<S: Shape, R: Rng + ?Sized>(shape: S, rng: &mut R) -> Option<Coord> {
+    let aabb = shape.aabb();
+    if aabb.size.is_empty() {
+        return None;
+    }
+
+    for _ in 0..REJECTION_ATTEMPTS {
+        let coord = Coord::new(
+            rng.gen_range(aabb.left()..=aabb.right()),
+            rng.gen_range(aabb.bottom()..=aabb.top()),
+        );
+        if shape.contains(coord) {
+            return Some(coord);
+        }
+    }
+
+    let count = shape.cell_count();
+    if count == 0 {
+        return None;
+    }
+    nth(shape, rng.gen_range(0..count))
+}
+
+/// Returns `amount` distinct uniformly random cells of `shape`
+#[cfg(feature = "alloc")]
+pub(crate) fn sample_cells<S: Shape, R: Rng + ?Sized>(
+    shape: S,
+    rng: &mut R,
+    amount: usize,
+) -> Vec<Coord> {
+    let mut cells = Vec::new();
+    shape.for_each(|coord| cells.push(coord));
+    sample_indices(rng, cells.len(), amount, |i| cells[i])
+}
+
+/// Returns `amount` distinct indices below `count` mapped by `f`
+#[cfg(feature = "alloc")]
+pub(crate) fn sample_indices<R: Rng + ?Sized>(
+    rng: &mut R,
+    count: usize,
+    amount: usize,
+    f: impl FnMut(usize) -> Coord,
+) -> Vec<Coord> {
+    rand::seq::index::sample(rng, count, amount.min(count))
+        .into_iter()
+        .map(f)
+        .collect()
+}
+
+/// How many candidates around an active cell are tried before it is retired
+#[cfg(feature = "std")]
+const POISSON_ATTEMPTS: usize = 30;
+
+/// Returns cells of `shape` at least `min_distance` apart using Bridson's algorithm
+///
+/// Once no more cells fit around the accepted ones, a few more cells are sampled from the whole
+/// shape so disconnected parts are filled too.
+#[cfg(feature = "std")]
+pub(crate) fn poisson_disk<S: Shape, R: Rng + ?Sized>(
+    shape: S,
+    rng: &mut R,
+    min_distance: u32,
+) -> Vec<Coord> {
+    let aabb = shape.aabb();
+    let min_distance = min_distance.max(1);
+    let min_distance2 = min_distance as u64 * min_distance as u64;
+
+    // Any two cells closer than `min_distance` are at most two background cells apart
+    let cell_size = min_distance as f32 / core::f32::consts::SQRT_2;
+    let background_size = |length: u32| (length as f32 / cell_size) as usize + 1;
+    let (width, height) = (
+        background_size(aabb.size.width()),
+        background_size(aabb.size.height()),
+    );
+    let background_coord = |coord: Coord| {
+        (
+            ((coord.x - aabb.left()) as f32 / cell_size) as usize,
+            ((coord.y - aabb.bottom()) as f32 / cell_size) as usize,
+        )
+    };
+    let mut background: Vec<Option<Coord>> = vec![None; width * height];
+
+    let fits = |coord: Coord, background: &[Option<Coord>]| {
+        let (x, y) = background_coord(coord);
+        (y.saturating_sub(2)..(y + 3).min(height)).all(|y| {
+            (x.saturating_sub(2)..(x + 3).min(width)).all(|x| match background[y * width + x] {
+                Some(other) => (coord.distance2(other) as u64) >= min_distance2,
+                None => true,
+            })
+        })
+    };
+
+    let mut cells = Vec::new();
+    let mut active = Vec::new();
+    let mut misses = 0;
+    while misses < POISSON_ATTEMPTS {
+        // Start from anywhere in the shape which still has room
+        match shape.sample(rng) {
+            Some(coord) if fits(coord, &background) => {
+                misses = 0;
+                let (x, y) = background_coord(coord);
+                background[y * width + x] = Some(coord);
+                cells.push(coord);
+                active.push(coord);
+            }
+            Some(_) => {
+                misses += 1;
+                continue;
+            }
+            None => break,
+        }
+
+        while !active.is_empty() {
+            let index = rng.gen_range(0..active.len());
+            let center = active[index];
+
+            // Candidates in the ring between one and two times `min_distance` away
+            let candidate = (0..POISSON_ATTEMPTS).find_map(|_| {
+                let angle = rng.gen_range(0.0..core::f32::consts::TAU);
+                let distance = rng.gen_range(min_distance as f32..2.0 * min_distance as f32);
+                let coord = Coord::new(
+                    center.x + (angle.cos() * distance).round() as i32,
+                    center.y + (angle.sin() * distance).round() as i32,
+                );
+                (shape.contains(coord) && aabb.contains(coord) && fits(coord, &background))
+                    .then_some(coord)
+            });
+
+            match candidate {
+                Some(coord) => {
+                    let (x, y) = background_coord(coord);
+                    background[y * width + x] = Some(coord);
+                    cells.push(coord);
+                    active.push(coord);
+                }
+                None => {
+                    active.swap_remove(index);
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// Returns the `n`th cell of `shape`
+fn nth<S: Shape>(shape: S, n: usize) -> Option<Coord> {
+    let mut i = 0;
+    let mut nth = None;
+    shape.for_each(|coord| {
+        if i == n {
+            nth = Some(coord);
+        }
+        i += 1;
+    });
+    nth
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Complement, Line, Rectangle};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::{HashMap, HashSet};
+
+    fn check(shape: impl Shape) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let count = shape.cell_count();
+
+        let mut seen = HashMap::new();
+        for _ in 0..count * 200 {
+            let coord = shape.sample(&mut rng).unwrap();
+            assert!(shape.contains(coord), "{:?} {:?}", shape, coord);
+            *seen.entry(coord).or_insert(0) += 1;
+        }
+        // Every cell should come up roughly as often as any other
+        assert_eq!(seen.len(), count);
+        assert!(
+            seen.values().all(|&n| (100..300).contains(&n)),
+            "{:?}",
+            shape
+        );
+
+        for amount in [0, 1, count / 2, count, count + 5] {
+            let cells = shape.sample_n(&mut rng, amount);
+            let unique: HashSet<Coord> = cells.iter().copied().collect();
+            assert_eq!(cells.len(), amount.min(count));
+            assert_eq!(unique.len(), cells.len());
+            assert!(cells.iter().all(|&coord| shape.contains(coord)));
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        check(Rectangle::new(0, 0, 0, 0));
+        check(Rectangle::new(-3, 2, 4, 5));
+    }
+
+    #[test]
+    fn test_rectangle_large() {
+        // More cells than fit in a u32
+        let rect = Rectangle::from_size(Coord::new(0, 0), coord_2d::Size::new(70001, 70001));
+        let cells = rect.sample_n(&mut StdRng::seed_from_u64(2), 20);
+        assert_eq!(cells.len(), 20);
+        assert!(cells.iter().all(|&coord| rect.contains(coord)));
+    }
+
+    #[test]
+    fn test_circle() {
+        check(Circle::new(Coord::new(0, 0), 0));
+        check(Circle::new(Coord::new(2, -3), 4));
+    }
+
+    #[test]
+    fn test_line() {
+        check(Line::new(Coord::new(-4, 2), Coord::new(9, -3)));
+    }
+
+    #[test]
+    fn test_fallback() {
+        // Only the corners of a large bounding box
+        let circle = Circle::new(Coord::new(0, 0), 30);
+        check(Complement::new(circle, circle.aabb()));
+        assert_eq!(
+            Complement::new(circle, Rectangle::new(-2, -2, 2, 2))
+                .sample(&mut StdRng::seed_from_u64(1)),
+            None
+        );
+    }
+
+    fn check_poisson(shape: impl Shape, min_distance: u32) {
+        let cells = shape.poisson_disk_sample(&mut StdRng::seed_from_u64(5), min_distance);
+        assert!(!cells.is_empty());
+        assert!(cells.iter().all(|&coord| shape.contains(coord)));
+        for (i, &a) in cells.iter().enumerate() {
+            for &b in &cells[i + 1..] {
+                assert!(
+                    a.distance2(b) >= min_distance * min_distance,
+                    "{:?} {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+        assert_eq!(
+            cells,
+            shape.poisson_disk_sample(&mut StdRng::seed_from_u64(5), min_distance)
+        );
+    }
+
+    #[test]
+    fn test_poisson_disk() {
+        check_poisson(Rectangle::new(0, 0, 0, 0), 3);
+        check_poisson(Rectangle::new(-20, 3, 40, 30), 1);
+        check_poisson(Rectangle::new(-20, 3, 40, 30), 4);
+        check_poisson(Circle::new(Coord::new(5, -5), 25), 6);
+        check_poisson(Line::new(Coord::new(-30, 2), Coord::new(25, 14)), 3);
+    }
+
+    #[test]
+    fn test_poisson_disk_coverage() {
+        // No cell of the shape is left without a sample nearby
+        let circle = Circle::new(Coord::new(0, 0), 20);
+        let shape = Complement::new(circle, circle.aabb().inflate(10, 10));
+        let cells = shape.poisson_disk_sample(&mut StdRng::seed_from_u64(9), 5);
+        check_poisson(shape, 5);
+        let covered = shape
+            .into_iter()
+            .filter(|&coord| cells.iter().any(|&cell| coord.distance2(cell) < 100))
+            .count();
+        assert!(covered as f32 > shape.cell_count() as f32 * 0.99);
+    }
+
+    #[test]
+    fn test_poisson_disk_empty() {
+        let circle = Circle::new(Coord::new(0, 0), 3);
+        let shape = Complement::new(circle, Rectangle::new(-1, -1, 1, 1));
+        assert!(shape
+            .poisson_disk_sample(&mut StdRng::seed_from_u64(1), 2)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let circle = Circle::new(Coord::new(2, -3), 12);
+        let a = circle.sample_n(&mut StdRng::seed_from_u64(3), 10);
+        let b = circle.sample_n(&mut StdRng::seed_from_u64(3), 10);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,61 @@
+//! Serialization for the `coord_2d` types used by the shapes
+//!
+//! `coord_2d/serialize` always pulls in `serde` with `std`, so the shapes serialize
+//! `Coord` and `Size` themselves, in the same format, to keep `serde` working without `std`.
+
+use coord_2d::{Coord, Size};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Coord", rename = "Coord")]
+pub(crate) struct CoordDef {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Size")]
+struct SizeDef {
+    x: u32,
+    y: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct CoordWrapper(#[serde(with = "CoordDef")] Coord);
+
+pub(crate) mod size {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(size: &Size, serializer: S) -> Result<S::Ok, S::Error> {
+        SizeDef {
+            x: size.width(),
+            y: size.height(),
+        }
+        .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Size, D::Error> {
+        let size = SizeDef::deserialize(deserializer)?;
+        Size::try_new(size.x, size.y).map_err(|_| D::Error::custom("size too large"))
+    }
+}
+
+pub(crate) mod option_coord {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        coord: &Option<Coord>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        coord.map(CoordWrapper).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Coord>, D::Error> {
+        Ok(Option::<CoordWrapper>::deserialize(deserializer)?.map(|coord| coord.0))
+    }
+}
@@ -1,11 +1,17 @@
-use std::{
+use core::{
     fmt::{Debug, Display},
     hash::Hash,
+    ops::RangeInclusive,
 };
 
+#[cfg(all(feature = "rand", feature = "alloc"))]
+use alloc::vec::Vec;
+
 use coord_2d::Coord;
+#[cfg(feature = "rand")]
+use rand::Rng;
 
-use super::Rectangle;
+use super::{Connectivity, DistanceMetric, Outline, OutlineKind, Rectangle};
 
 pub trait Shape:
     Debug + Display + Clone + Copy + PartialEq + Eq + Hash + Default + IntoIterator
@@ -13,6 +19,220 @@ pub trait Shape:
     /// Calls `f` for each Coord in the shape
     fn for_each<F: FnMut(Coord)>(self, f: F);
 
+    /// Calls `f` for each Coord in the shape which is inside `bounds`
+    fn for_each_within<F: FnMut(Coord)>(self, bounds: Rectangle, mut f: F) {
+        self.for_each(|coord| {
+            if bounds.contains(coord) {
+                f(coord);
+            }
+        });
+    }
+
+    /// Provides an iterator over the runs of cells in each row of the shape as `(y, x_start..=x_end)`
+    ///
+    /// Every cell of the shape is in exactly one span and spans in the same row never touch.
+    fn spans(self) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        self.spans_within(self.aabb())
+    }
+
+    /// Provides an iterator over the spans of the shape cut down to `bounds`, see [`Shape::spans`]
+    ///
+    /// Rows and columns outside of `bounds` are skipped rather than visited.
+    fn spans_within(self, bounds: Rectangle) -> impl Iterator<Item = (i32, RangeInclusive<i32>)> {
+        self.aabb()
+            .intersection(bounds)
+            .into_iter()
+            .flat_map(move |overlap| {
+                (overlap.bottom()..=overlap.top()).flat_map(move |y| {
+                    // Walked in i64 so a right edge at `i32::MAX` can't overflow
+                    let contains = move |x: i64| self.contains(Coord::new(x as i32, y));
+                    let right = overlap.right() as i64;
+                    let mut x = overlap.left() as i64;
+                    core::iter::from_fn(move || {
+                        // Skip to the start of the next run, then to its end, checking each cell once
+                        while x <= right && !contains(x) {
+                            x += 1;
+                        }
+                        if x > right {
+                            return None;
+                        }
+                        let start = x;
+                        x += 1;
+                        while x <= right && contains(x) {
+                            x += 1;
+                        }
+                        let end = x - 1;
+                        // The cell ending the run has already been checked
+                        x += 1;
+                        Some((y, start as i32..=end as i32))
+                    })
+                })
+            })
+    }
+
     /// Returns an axis aligned bounding box containing the shape
     fn aabb(self) -> Rectangle;
+
+    /// Determine if a position is part of the shape
+    fn contains(self, position: Coord) -> bool;
+
+    /// Returns the smallest distance from `position` to any cell of the shape
+    ///
+    /// Returns `f32::INFINITY` if the shape has no cells.
+    fn distance(self, position: Coord, metric: DistanceMetric) -> f32 {
+        self.closest_cell(position, metric)
+            .map_or(f32::INFINITY, |coord| metric.distance(position, coord))
+    }
+
+    /// Returns the cell of the shape closest to `position`
+    ///
+    /// Ties are broken as described by [`DistanceMetric::is_closer`]. Returns `None` if the shape
+    /// has no cells.
+    fn closest_cell(self, position: Coord, metric: DistanceMetric) -> Option<Coord> {
+        let mut closest = None;
+        self.for_each(|coord| match closest {
+            Some(c) if !metric.is_closer(position, coord, c) => {}
+            _ => closest = Some(coord),
+        });
+        closest
+    }
+
+    /// Returns the number of cells in the shape
+    fn cell_count(self) -> usize {
+        self.into_iter().count()
+    }
+
+    /// Returns a uniformly random cell of the shape
+    ///
+    /// Returns `None` if the shape has no cells.
+    #[cfg(feature = "rand")]
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> Option<Coord> {
+        super::sample::rejection_sample(self, rng)
+    }
+
+    /// Returns `amount` distinct uniformly random cells of the shape
+    ///
+    /// Returns every cell of the shape in a random order if it has fewer than `amount` cells.
+    #[cfg(all(feature = "rand", feature = "alloc"))]
+    fn sample_n<R: Rng + ?Sized>(self, rng: &mut R, amount: usize) -> Vec<Coord> {
+        super::sample::sample_cells(self, rng, amount)
+    }
+
+    /// Returns well spaced random cells of the shape using Poisson-disk sampling
+    ///
+    /// No two cells are closer than `min_distance` (measured in a straight line) and no more
+    /// cells fit in between. The same seed always gives the same cells.
+    #[cfg(all(feature = "rand", feature = "std"))]
+    fn poisson_disk_sample<R: Rng + ?Sized>(self, rng: &mut R, min_distance: u32) -> Vec<Coord> {
+        super::sample::poisson_disk(self, rng, min_distance)
+    }
+
+    /// Provides an iterator over the perimeter of the shape
+    ///
+    /// The perimeter is every cell of the shape with an orthogonal neighbour outside of it, the
+    /// same cells as `self.outline(Connectivity::Four, OutlineKind::Inner)`.
+    fn perimeter_iter(self) -> impl Iterator<Item = Coord> {
+        self.outline(Connectivity::Four, OutlineKind::Inner)
+            .into_iter()
+    }
+
+    /// Returns the number of cells in the perimeter of the shape
+    fn perimeter_len(self) -> usize {
+        self.perimeter_iter().count()
+    }
+
+    /// Returns the outline of the shape
+    fn outline(self, connectivity: Connectivity, kind: OutlineKind) -> Outline<Self> {
+        Outline::new(self, connectivity, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Clipped, Complement, Line};
+    use coord_2d::Size;
+
+    fn check_spans(shape: impl Shape) {
+        let mut spans: Vec<(i32, RangeInclusive<i32>)> = shape.spans().collect();
+        spans.sort_by_key(|(y, span)| (*y, *span.start()));
+        // Spans of the same row never touch
+        for pair in spans.windows(2) {
+            if pair[0].0 == pair[1].0 {
+                assert!(pair[0].1.end() + 1 < *pair[1].1.start(), "{:?}", shape);
+            }
+        }
+
+        let mut cells: Vec<Coord> = spans
+            .into_iter()
+            .flat_map(|(y, span)| span.map(move |x| Coord::new(x, y)))
+            .collect();
+        let mut expected = Vec::new();
+        shape.for_each(|coord| expected.push(coord));
+        cells.sort();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(cells, expected, "{:?}", shape);
+
+        // Spans within bounds are the spans cut down to the bounds
+        let aabb = shape.aabb();
+        for bounds in [
+            aabb,
+            Rectangle::new(
+                aabb.left() + 1,
+                aabb.bottom() - 2,
+                aabb.right() + 3,
+                aabb.top() - 1,
+            ),
+            Rectangle::new(-1, -1, 1, 1),
+            Rectangle::from_size(Coord::new(0, 0), Size::new(0, 3)),
+            Rectangle::new(100, 100, 120, 120),
+        ] {
+            let mut within: Vec<(i32, RangeInclusive<i32>)> = shape.spans_within(bounds).collect();
+            let mut expected: Vec<(i32, RangeInclusive<i32>)> = shape
+                .spans()
+                .filter(|(y, _)| bounds.bottom() <= *y && *y <= bounds.top())
+                .filter_map(|(y, span)| {
+                    let left = (*span.start()).max(bounds.left());
+                    let right = (*span.end()).min(bounds.right());
+                    (left <= right).then_some((y, left..=right))
+                })
+                .collect();
+            within.sort_by_key(|(y, span)| (*y, *span.start()));
+            expected.sort_by_key(|(y, span)| (*y, *span.start()));
+            assert_eq!(within, expected, "{:?} {:?}", shape, bounds);
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        for size in [
+            Size::new(0, 0),
+            Size::new(0, 3),
+            Size::new(1, 1),
+            Size::new(4, 3),
+        ] {
+            check_spans(Rectangle::from_size(Coord::new(-2, 1), size));
+        }
+        for radius in 0..20 {
+            check_spans(Circle::new(Coord::new(1, -3), radius));
+        }
+        for end in Rectangle::new(-4, -4, 4, 4) {
+            check_spans(Line::new(Coord::new(0, 0), end));
+        }
+    }
+
+    #[test]
+    fn test_spans_composite() {
+        let circle = Circle::new(Coord::new(0, 0), 6);
+        check_spans(Complement::new(circle, Rectangle::new(-8, -7, 8, 7)));
+        check_spans(circle.outline(Connectivity::Eight, OutlineKind::Outer));
+        for bounds in [Rectangle::new(-2, -9, 3, 2), Rectangle::new(10, 10, 12, 12)] {
+            check_spans(Clipped::new(circle, bounds));
+            check_spans(Clipped::new(
+                Line::new(Coord::new(-9, -3), Coord::new(9, 4)),
+                bounds,
+            ));
+        }
+    }
 }
@@ -0,0 +1,78 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+};
+
+use coord_2d::Coord;
+
+use super::{Rectangle, Shape};
+
+/// Checks walking `iter` from the back gives the same cells as from the front
+pub(crate) fn check_double_ended(iter: impl DoubleEndedIterator<Item = Coord> + Clone) {
+    let forward: Vec<Coord> = iter.clone().collect();
+    let mut backward: Vec<Coord> = iter.clone().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    // Taking from both ends meets in the middle
+    let mut iter = iter;
+    let (mut front, mut back) = (Vec::new(), Vec::new());
+    for i in 0.. {
+        let next = if i % 3 == 0 {
+            iter.next_back()
+        } else {
+            iter.next()
+        };
+        match next {
+            Some(coord) if i % 3 == 0 => back.push(coord),
+            Some(coord) => front.push(coord),
+            None => break,
+        }
+    }
+    front.extend(back.into_iter().rev());
+    assert_eq!(front, forward);
+}
+
+static CONTAINS_CALLS: AtomicUsize = AtomicUsize::new(0);
+static CONTAINS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A shape counting how many cells are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) struct Counted<S>(pub(crate) S);
+
+impl<S: Shape> core::fmt::Display for Counted<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Counted({})", self.0)
+    }
+}
+
+impl<S: Shape> IntoIterator for Counted<S> {
+    type IntoIter = S::IntoIter;
+    type Item = S::Item;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<S: Shape> Shape for Counted<S> {
+    fn for_each<F: FnMut(Coord)>(self, f: F) {
+        self.0.for_each(f);
+    }
+
+    fn aabb(self) -> Rectangle {
+        self.0.aabb()
+    }
+
+    fn contains(self, position: Coord) -> bool {
+        CONTAINS_CALLS.fetch_add(1, Ordering::Relaxed);
+        self.0.contains(position)
+    }
+}
+
+/// Runs `f`, returning how many cells `Counted` shapes checked
+pub(crate) fn count_contains_calls(f: impl FnOnce()) -> usize {
+    let _lock = CONTAINS_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    CONTAINS_CALLS.store(0, Ordering::Relaxed);
+    f();
+    CONTAINS_CALLS.load(Ordering::Relaxed)
+}